@@ -0,0 +1,124 @@
+//! Parsing of two-character card notation, and the 52-card index order
+//!
+//! `Card` comes from bridge-types, so `FromStr` cannot be implemented for it
+//! here; `parse_card()` provides the same conversion. `CardExt` adds the
+//! full pack the same way `SuitExt` and `RankExt` extend `Suit` and `Rank`.
+
+use crate::{Card, Rank, RankExt, Suit, SuitExt};
+
+/// The full pack, in `Card::to_index()` order
+///
+/// The index of a card is `13 * suit + rank`, with suits ordered clubs,
+/// diamonds, hearts, spades and ranks two to ace: 0 is the club two, 12 the
+/// club ace and 51 the spade ace. This is dealer.c's pack order, which the
+/// deal generators rely on to reproduce dealer.exe's deals, and
+/// `Card::from_index(card.to_index())` gives back `card` for every card.
+pub trait CardExt: Sized {
+    /// All 52 cards, from index 0 to 51
+    fn all() -> impl Iterator<Item = Self>;
+}
+
+impl CardExt for Card {
+    fn all() -> impl Iterator<Item = Card> {
+        (0..52).filter_map(Card::from_index)
+    }
+}
+
+/// Error returned when a card string cannot be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardParseError {
+    pub message: String,
+}
+
+impl std::fmt::Display for CardParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Card parse error: {}", self.message)
+    }
+}
+
+impl std::error::Error for CardParseError {}
+
+/// Parse a card written rank first, then suit: "AS", "TH", "2C"
+///
+/// Case-insensitive. The order is always rank then suit (as in dealer's
+/// `hascard`), so "SA" is rejected rather than guessed at. Ten is written `T`.
+pub fn parse_card(s: &str) -> Result<Card, CardParseError> {
+    let mut chars = s.chars();
+    let (Some(rank_char), Some(suit_char), None) = (chars.next(), chars.next(), chars.next())
+    else {
+        return Err(CardParseError {
+            message: format!("Expected rank and suit like \"AS\", got \"{}\"", s),
+        });
+    };
+
+    let Some(rank) = Rank::from_char(rank_char) else {
+        return Err(CardParseError {
+            message: format!(
+                "Invalid rank '{}' in \"{}\" (rank comes first)",
+                rank_char, s
+            ),
+        });
+    };
+
+    let Some(suit) = Suit::from_char(suit_char) else {
+        return Err(CardParseError {
+            message: format!("Invalid suit '{}' in \"{}\"", suit_char, s),
+        });
+    };
+
+    Ok(Card::new(suit, rank))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_card_suits() {
+        assert_eq!(parse_card("AS"), Ok(Card::new(Suit::Spades, Rank::Ace)));
+        assert_eq!(parse_card("KH"), Ok(Card::new(Suit::Hearts, Rank::King)));
+        assert_eq!(parse_card("TD"), Ok(Card::new(Suit::Diamonds, Rank::Ten)));
+        assert_eq!(parse_card("2C"), Ok(Card::new(Suit::Clubs, Rank::Two)));
+    }
+
+    #[test]
+    fn test_parse_card_lowercase() {
+        assert_eq!(parse_card("th"), Ok(Card::new(Suit::Hearts, Rank::Ten)));
+        assert_eq!(parse_card("qS"), Ok(Card::new(Suit::Spades, Rank::Queen)));
+    }
+
+    #[test]
+    fn test_parse_card_errors() {
+        // No rank 1, and "10" is not accepted for the ten
+        assert!(parse_card("1S").is_err());
+        assert!(parse_card("10H").is_err());
+        // Suit first is rejected, not reinterpreted
+        let err = parse_card("SA").unwrap_err();
+        assert!(err.message.contains("rank comes first"));
+        assert!(parse_card("").is_err());
+        assert!(parse_card("A").is_err());
+        assert!(parse_card("AX").is_err());
+    }
+
+    #[test]
+    fn test_all_cards_in_index_order() {
+        let cards: Vec<Card> = Card::all().collect();
+        assert_eq!(cards.len(), 52);
+        for (index, card) in cards.iter().enumerate() {
+            assert_eq!(card.to_index() as usize, index);
+            assert_eq!(Card::from_index(card.to_index()), Some(*card));
+        }
+
+        // No card appears twice
+        let mut seen = std::collections::HashSet::new();
+        assert!(cards.iter().all(|card| seen.insert(*card)));
+
+        // Clubs first, two to ace; spades last
+        assert_eq!(cards[0], Card::new(Suit::Clubs, Rank::Two));
+        assert_eq!(cards[12], Card::new(Suit::Clubs, Rank::Ace));
+        assert_eq!(cards[13], Card::new(Suit::Diamonds, Rank::Two));
+        assert_eq!(cards[39], Card::new(Suit::Spades, Rank::Two));
+        assert_eq!(cards[51], Card::new(Suit::Spades, Rank::Ace));
+        assert_eq!(Card::from_index(52), None);
+    }
+}
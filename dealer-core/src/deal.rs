@@ -1,5 +1,6 @@
-use crate::{Card, Hand, Position};
+use crate::{Card, Hand, Position, PositionExt, Side, Suit, SuitExt};
 use gnurandom::{GnuRandom, GnuRandomState};
+use std::cmp::Reverse;
 
 /// Represents a complete bridge deal (4 hands of 13 cards each)
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -21,6 +22,37 @@ impl Deal {
         }
     }
 
+    /// Build a deal from four hands in North, East, South, West order
+    ///
+    /// Debug builds assert that each hand has 13 cards and that no card
+    /// appears twice.
+    pub fn from_hands(hands: [Hand; 4]) -> Self {
+        debug_assert!(
+            hands.iter().all(|hand| hand.len() == 13),
+            "every hand of a deal must have 13 cards"
+        );
+        debug_assert!(
+            {
+                let mut seen = 0u64;
+                hands.iter().flat_map(|hand| hand.cards()).all(|card| {
+                    let bit = 1u64 << card.to_index();
+                    let fresh = seen & bit == 0;
+                    seen |= bit;
+                    fresh
+                })
+            },
+            "a card appears twice in the deal"
+        );
+
+        let [north, east, south, west] = hands;
+        Deal {
+            north,
+            east,
+            south,
+            west,
+        }
+    }
+
     /// Get a reference to a hand by position
     pub fn hand(&self, position: Position) -> &Hand {
         match position {
@@ -41,6 +73,64 @@ impl Deal {
         }
     }
 
+    /// Exchange the hands of two positions
+    pub fn swap_hands(&mut self, a: Position, b: Position) {
+        if a == b {
+            return;
+        }
+        let hand_a = std::mem::take(self.hand_mut(a));
+        let hand_b = std::mem::replace(self.hand_mut(b), hand_a);
+        *self.hand_mut(a) = hand_b;
+    }
+
+    /// The deal with every hand moved `steps` seats clockwise
+    ///
+    /// `rotated(1)` gives North's hand to East, East's to South, and so on.
+    pub fn rotated(&self, steps: usize) -> Deal {
+        let mut deal = Deal::new();
+        for position in Position::ALL {
+            *deal.hand_mut(position.rotate(steps)) = self.hand(position).clone();
+        }
+        deal
+    }
+
+    /// Whether `other` is this deal turned zero to three seats around the table
+    ///
+    /// Card order within a hand is ignored.
+    pub fn is_rotation_of(&self, other: &Deal) -> bool {
+        let masks = self.card_masks();
+        (0..4).any(|steps| other.rotated(steps).card_masks() == masks)
+    }
+
+    /// A 64-bit key identifying the deal, for deduplicating generated sets
+    ///
+    /// Card order within a hand is ignored. With `up_to_rotation` set, all
+    /// four rotations of a deal share a key, so a deal and its NS/EW exchange
+    /// count once. Distinct deals can collide, so confirm a match with `==`
+    /// or `is_rotation_of()`.
+    pub fn canonical_key(&self, up_to_rotation: bool) -> u64 {
+        let key = |masks: [u64; 4]| {
+            masks.iter().fold(0u64, |key, &mask| {
+                (key ^ mask)
+                    .wrapping_mul(0x9E37_79B9_7F4A_7C15)
+                    .rotate_left(31)
+            })
+        };
+        if up_to_rotation {
+            (0..4)
+                .map(|steps| key(self.rotated(steps).card_masks()))
+                .min()
+                .unwrap_or(0)
+        } else {
+            key(self.card_masks())
+        }
+    }
+
+    /// Card masks of the four hands in North, East, South, West order
+    fn card_masks(&self) -> [u64; 4] {
+        Position::ALL.map(|position| self.hand(position).card_mask())
+    }
+
     /// Sort all hands in the deal
     pub fn sort_all_hands(&mut self) {
         self.north.sort();
@@ -48,6 +138,58 @@ impl Deal {
         self.south.sort();
         self.west.sort();
     }
+
+    /// Combined HCP of a partnership
+    pub fn side_hcp(&self, side: Side) -> u8 {
+        side.positions()
+            .iter()
+            .map(|&pos| self.hand(pos).hcp())
+            .sum()
+    }
+
+    /// Combined length of a suit in a partnership, e.g. 8 for a 5-3 fit
+    pub fn combined_suit_length(&self, side: Side, suit: Suit) -> usize {
+        side.positions()
+            .iter()
+            .map(|&pos| self.hand(pos).suit_length(suit))
+            .sum()
+    }
+
+    /// HCP across all four hands (40 for any complete deal)
+    pub fn total_hcp(&self) -> u8 {
+        Position::ALL.iter().map(|&pos| self.hand(pos).hcp()).sum()
+    }
+
+    /// Format as the body of a PBN `[Deal]` tag, e.g. `"N:AKQT3.J6.KJ42.95 ..."`
+    ///
+    /// Hands are listed clockwise starting from `leader`, suits in S.H.D.C
+    /// order with ranks high to low. A void is an empty suit.
+    pub fn to_pbn_string(&self, leader: Position) -> String {
+        let mut result = String::with_capacity(69);
+        result.push(match leader {
+            Position::North => 'N',
+            Position::East => 'E',
+            Position::South => 'S',
+            Position::West => 'W',
+        });
+        result.push(':');
+
+        for i in 0..4 {
+            if i > 0 {
+                result.push(' ');
+            }
+            let hand = self.hand(leader.rotate(i));
+            for (j, suit) in Suit::DESCENDING.into_iter().enumerate() {
+                if j > 0 {
+                    result.push('.');
+                }
+                let mut cards = hand.cards_in_suit(suit);
+                cards.sort_by_key(|c| Reverse(c.rank));
+                result.extend(cards.iter().map(|c| c.rank.to_char()));
+            }
+        }
+        result
+    }
 }
 
 impl Default for Deal {
@@ -89,6 +231,20 @@ pub struct DealGeneratorState {
     stacked_pack: [Option<u8>; 52],
 }
 
+/// Hand exchanges applied in turn after each shuffle in swapping mode.
+/// Alternating E-W and E-S visits all six arrangements of East, South and West.
+const SWAP_STEPS_2: [(Position, Position); 1] = [(Position::East, Position::West)];
+const SWAP_STEPS_3: [(Position, Position); 5] = [
+    (Position::East, Position::West),
+    (Position::East, Position::South),
+    (Position::East, Position::West),
+    (Position::East, Position::South),
+    (Position::East, Position::West),
+];
+
+/// Weighting function set by DealGenerator::set_acceptance_weight()
+type AcceptanceWeight = Box<dyn Fn(&Deal) -> f64 + Send>;
+
 /// Generator for creating random bridge deals
 pub struct DealGenerator {
     rng: GnuRandom,
@@ -96,6 +252,11 @@ pub struct DealGenerator {
     curdeal: [u8; 52],              // Current deal (slot-indexed, matches dealer.c)
     fullpack: [Option<u8>; 52],     // Full pack with predealt cards marked as None
     stacked_pack: [Option<u8>; 52], // Predealt cards (matches dealer.c's stacked_pack)
+    swapping: u8,                   // 0 (off), 2 or 3 (matches dealer.c's swapping)
+    swap_step: usize,               // Next entry of the swap steps to apply
+    last_deal: Option<Deal>,        // Last deal returned, base for the next swap
+    acceptance_weight: Option<AcceptanceWeight>,
+    weighted_current: Option<(Deal, f64)>, // Current state of the Metropolis chain
 }
 
 impl DealGenerator {
@@ -124,6 +285,11 @@ impl DealGenerator {
             curdeal,
             fullpack,
             stacked_pack,
+            swapping: 0,
+            swap_step: 0,
+            last_deal: None,
+            acceptance_weight: None,
+            weighted_current: None,
         };
         gen.rebuild_zero52();
         // Set up the initial deal (matches dealer.c calling setup_deal once before loop)
@@ -189,6 +355,9 @@ impl DealGenerator {
     /// This matches predeal() in dealer.c
     /// Returns an error if more than 13 cards are dealt to one position or if a card is dealt twice
     pub fn predeal(&mut self, position: Position, cards: &[Card]) -> Result<(), String> {
+        if self.swapping != 0 {
+            return Err("Predeal cannot be combined with swapping".to_string());
+        }
         let pos_offset = (position as usize) * 13;
 
         for &card in cards {
@@ -227,10 +396,110 @@ impl DealGenerator {
         Ok(())
     }
 
+    /// Set the swapping mode (matches dealer.exe's -2 and -3 switches)
+    /// - 0: off, every deal is a fresh shuffle
+    /// - 2: each shuffle is followed by the deal with East and West exchanged
+    /// - 3: each shuffle is followed by the other 5 arrangements of East, South and West
+    ///
+    /// Swapping multiplies throughput when the condition is symmetric. It only
+    /// affects generate() (and so generate_many() and iter()), and returns an error
+    /// if cards have been predealt, since swapping would move them.
+    pub fn set_swapping(&mut self, swapping: u8) -> Result<(), String> {
+        if !matches!(swapping, 0 | 2 | 3) {
+            return Err(format!(
+                "Invalid swapping mode {}, expected 0, 2 or 3",
+                swapping
+            ));
+        }
+        if swapping != 0 && self.stacked_pack.iter().any(Option::is_some) {
+            return Err("Swapping cannot be combined with predeal".to_string());
+        }
+        self.swapping = swapping;
+        self.swap_step = 0;
+        self.last_deal = None;
+        Ok(())
+    }
+
+    /// Current swapping mode (0, 2 or 3)
+    pub fn swapping(&self) -> u8 {
+        self.swapping
+    }
+
+    /// Generate the next deal
+    /// Without swapping this is a fresh shuffle; with swapping, each shuffle is
+    /// followed by its swapped variants (see set_swapping())
+    pub fn generate(&mut self) -> Deal {
+        let steps: &[(Position, Position)] = match self.swapping {
+            2 => &SWAP_STEPS_2,
+            3 => &SWAP_STEPS_3,
+            _ => return self.weighted_shuffle(),
+        };
+
+        let deal = match self.last_deal.take() {
+            Some(mut deal) if self.swap_step < steps.len() => {
+                let (a, b) = steps[self.swap_step];
+                deal.swap_hands(a, b);
+                self.swap_step += 1;
+                deal
+            }
+            _ => {
+                self.swap_step = 0;
+                self.weighted_shuffle()
+            }
+        };
+        self.last_deal = Some(deal.clone());
+        deal
+    }
+
+    /// Bias generate() toward deals with a high `weight`
+    ///
+    /// Each shuffle becomes a proposal in an independence Metropolis chain: it
+    /// replaces the current deal with probability min(1, w(new) / w(current)),
+    /// and otherwise the current deal is returned again. Over many deals the
+    /// sample approximates the uniform distribution reweighted by `weight`.
+    /// Negative and NaN weights count as zero.
+    ///
+    /// Statistical caveat: consecutive deals are correlated and repeats are
+    /// expected, so a small sample is not an independent draw, and averages
+    /// converge more slowly than with uniform generation. The acceptance test
+    /// draws from the same GnuRandom stream, so output stays deterministic per
+    /// seed but differs from the unweighted sequence.
+    pub fn set_acceptance_weight<F: Fn(&Deal) -> f64 + Send + 'static>(&mut self, weight: F) {
+        self.acceptance_weight = Some(Box::new(weight));
+        self.weighted_current = None;
+    }
+
+    /// Next shuffle, passed through the acceptance weight if one is set
+    fn weighted_shuffle(&mut self) -> Deal {
+        let proposal = self.shuffle();
+        let Some(weight) = &self.acceptance_weight else {
+            return proposal;
+        };
+        let proposal_weight = weight(&proposal).max(0.0);
+
+        let accept = match &self.weighted_current {
+            None => true,
+            Some((_, current_weight)) if *current_weight <= 0.0 => true,
+            Some((_, current_weight)) => {
+                let ratio = proposal_weight / current_weight;
+                let uniform = self.rng.next_u32() as f64 / 4_294_967_296.0;
+                ratio >= 1.0 || uniform < ratio
+            }
+        };
+
+        match &mut self.weighted_current {
+            Some((deal, _)) if !accept => deal.clone(),
+            current => {
+                *current = Some((proposal.clone(), proposal_weight));
+                proposal
+            }
+        }
+    }
+
     /// Generate a random deal using Knuth's shuffle algorithm
     /// This exactly matches dealer.exe's shuffle implementation with predeal support
     /// NOTE: Each call reshuffles the SAME curdeal (not a fresh sorted deck)
-    pub fn generate(&mut self) -> Deal {
+    fn shuffle(&mut self) -> Deal {
         // Knuth's shuffle algorithm (forward iteration, as in dealer.c)
         // For each slot i, swap with a random slot j (0 <= j <= 51)
         // IMPORTANT: We shuffle the existing curdeal, not a fresh sorted one!
@@ -280,6 +549,54 @@ impl DealGenerator {
         (0..count).map(|_| self.generate()).collect()
     }
 
+    /// Iterate over an endless stream of deals
+    /// Yields exactly the same sequence as repeated calls to generate()
+    pub fn iter(&mut self) -> impl Iterator<Item = Deal> + '_ {
+        std::iter::repeat_with(move || self.generate())
+    }
+
+    /// Generate deals until one satisfies `pred`, giving up after `max_tries` deals
+    ///
+    /// Returns `None` if no deal matched, so callers can detect constraints that
+    /// are impossible or too rare for the cap. Every attempt consumes a deal from
+    /// the stream, matching or not.
+    pub fn generate_satisfying<F: Fn(&Deal) -> bool>(
+        &mut self,
+        pred: F,
+        max_tries: usize,
+    ) -> Option<Deal> {
+        self.iter().take(max_tries).find(|deal| pred(deal))
+    }
+
+    /// Restart the deal sequence from `seed`, as if the generator had just been built
+    ///
+    /// Predeal, swapping and acceptance weight settings are kept, so the deals
+    /// that follow match a fresh `DealGenerator::new(seed)` given the same settings.
+    pub fn reseed(&mut self, seed: u32) {
+        self.rng.srandom(seed);
+        self.setup_deal();
+        self.swap_step = 0;
+        self.last_deal = None;
+        self.weighted_current = None;
+    }
+
+    /// Skip `n` deals, leaving the generator exactly where `n` calls to generate() would
+    ///
+    /// Without swapping only the shuffle is replayed; the hands are never built.
+    /// With swapping or an acceptance weight the next deal depends on the previous
+    /// one, so the skipped deals are generated.
+    pub fn skip(&mut self, n: usize) {
+        if self.swapping != 0 || self.acceptance_weight.is_some() {
+            for _ in 0..n {
+                self.generate();
+            }
+        } else {
+            for _ in 0..n {
+                self.advance_one_deal();
+            }
+        }
+    }
+
     /// Advance the RNG state as if generating one deal, but don't do the actual shuffle.
     /// This is an optimization for parallel batch generation where we only need to capture
     /// the RNG state and advance it, without doing the full shuffle work.
@@ -329,6 +646,11 @@ impl DealGenerator {
             curdeal: state.curdeal,
             fullpack: state.fullpack,
             stacked_pack: state.stacked_pack,
+            swapping: 0,
+            swap_step: 0,
+            last_deal: None,
+            acceptance_weight: None,
+            weighted_current: None,
         }
     }
 
@@ -391,6 +713,16 @@ impl DealGenerator {
         }
     }
 
+    /// Resume from a work state captured by `capture_work_state()`.
+    /// The generator must have the same predeal as the one the state came from;
+    /// it then continues with exactly the deals the original would have produced.
+    /// Together with `GnuRandomState::from_parts()` this lets a long run be
+    /// checkpointed and resumed mid-stream.
+    pub fn restore_work_state(&mut self, work_state: DealWorkState) {
+        self.rng = GnuRandom::from_state(work_state.rng_state);
+        self.curdeal = work_state.curdeal;
+    }
+
     /// Generate a deal from work state using shared config.
     /// This is the efficient parallel generation path - config is shared, only work_state is cloned.
     pub fn generate_from_work_state(
@@ -463,6 +795,150 @@ mod tests {
         assert_eq!(deal1, deal2);
     }
 
+    #[test]
+    fn test_iter_matches_generate() {
+        let mut gen1 = DealGenerator::new(42);
+        let mut gen2 = DealGenerator::new(42);
+
+        let from_iter: Vec<Deal> = gen1.iter().take(20).collect();
+        let from_generate = gen2.generate_many(20);
+        assert_eq!(from_iter, from_generate);
+
+        // The generator continues where the iterator left off
+        assert_eq!(gen1.generate(), gen2.generate());
+    }
+
+    #[test]
+    fn test_iter_filter() {
+        let mut gen = DealGenerator::new(1);
+        let strong: Vec<Deal> = gen
+            .iter()
+            .filter(|d| d.hand(Position::North).hcp() >= 15)
+            .take(3)
+            .collect();
+        assert_eq!(strong.len(), 3);
+        assert!(strong.iter().all(|d| d.hand(Position::North).hcp() >= 15));
+    }
+
+    #[test]
+    fn test_generate_satisfying() {
+        let north_hcp = |d: &Deal| d.hand(Position::North).hcp();
+
+        // A rare constraint with a small cap: either a real match or None
+        let mut gen = DealGenerator::new(1);
+        if let Some(deal) = gen.generate_satisfying(|d| north_hcp(d) >= 30, 100) {
+            assert!(north_hcp(&deal) >= 30);
+        }
+
+        // Impossible constraint exhausts the cap
+        let mut gen = DealGenerator::new(1);
+        assert_eq!(gen.generate_satisfying(|d| north_hcp(d) > 37, 50), None);
+
+        // A common constraint returns the same deal as filtering the stream
+        let mut gen = DealGenerator::new(1);
+        let found = gen.generate_satisfying(|d| north_hcp(d) >= 15, 1000);
+        let mut gen = DealGenerator::new(1);
+        let expected = gen.iter().find(|d| north_hcp(d) >= 15);
+        assert!(found.is_some());
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn test_skip_matches_generate() {
+        let mut skipped = DealGenerator::new(42);
+        skipped.skip(5);
+        let sixth = DealGenerator::new(42).generate_many(6).pop();
+        assert_eq!(Some(skipped.generate()), sixth);
+
+        // Swapping mode steps through the swapped variants too
+        let mut skipped = DealGenerator::new(42);
+        skipped.set_swapping(3).unwrap();
+        skipped.skip(8);
+        let mut gen = DealGenerator::new(42);
+        gen.set_swapping(3).unwrap();
+        let ninth = gen.generate_many(9).pop();
+        assert_eq!(Some(skipped.generate()), ninth);
+    }
+
+    #[test]
+    fn test_reseed_matches_new() {
+        let mut gen = DealGenerator::new(42);
+        gen.skip(3);
+        gen.reseed(1);
+        assert_eq!(gen.generate(), DealGenerator::new(1).generate());
+        assert_eq!(
+            gen.generate_many(5),
+            DealGenerator::new(1).generate_many(6)[1..].to_vec()
+        );
+
+        // Predeal survives the reseed
+        let cards = [Card::new(Suit::Spades, Rank::Ace)];
+        let mut gen = DealGenerator::new(7);
+        gen.predeal(Position::South, &cards).unwrap();
+        gen.generate_many(3);
+        gen.reseed(9);
+        let mut fresh = DealGenerator::new(9);
+        fresh.predeal(Position::South, &cards).unwrap();
+        assert_eq!(gen.generate_many(4), fresh.generate_many(4));
+
+        // Reseeding part way through a swap cycle starts a new cycle
+        let mut gen = DealGenerator::new(7);
+        gen.set_swapping(3).unwrap();
+        gen.generate_many(2);
+        gen.reseed(9);
+        let mut fresh = DealGenerator::new(9);
+        fresh.set_swapping(3).unwrap();
+        assert_eq!(gen.generate_many(7), fresh.generate_many(7));
+    }
+
+    #[test]
+    fn test_acceptance_weight_biases_sample() {
+        let north_spades = |d: &Deal| d.hand(Position::North).suit_length(Suit::Spades);
+        let average = |gen: &mut DealGenerator| {
+            let total: usize = gen.iter().take(2000).map(|d| north_spades(&d)).sum();
+            total as f64 / 2000.0
+        };
+
+        let uniform = average(&mut DealGenerator::new(1));
+
+        let mut weighted = DealGenerator::new(1);
+        weighted.set_acceptance_weight(move |d| 4f64.powi(north_spades(d) as i32));
+        let biased = average(&mut weighted);
+
+        // Uniform averages 3.25 spades; the weight should push well past that
+        assert!(uniform < 3.5, "uniform average {}", uniform);
+        assert!(biased > uniform + 1.0, "biased {} vs {}", biased, uniform);
+
+        // Still deterministic per seed
+        let mut again = DealGenerator::new(1);
+        again.set_acceptance_weight(move |d| 4f64.powi(north_spades(d) as i32));
+        assert_eq!(average(&mut again), biased);
+    }
+
+    #[test]
+    fn test_from_hands() {
+        let original = DealGenerator::new(1).generate();
+        let hands = Position::ALL.map(|position| original.hand(position).clone());
+
+        let deal = Deal::from_hands(hands);
+        assert_eq!(deal.hand(Position::East), original.hand(Position::East));
+        assert_eq!(deal, original);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "a card appears twice")]
+    fn test_from_hands_rejects_duplicates() {
+        let original = DealGenerator::new(1).generate();
+        let north = original.hand(Position::North).clone();
+        Deal::from_hands([
+            north.clone(),
+            north,
+            original.hand(Position::South).clone(),
+            original.hand(Position::West).clone(),
+        ]);
+    }
+
     #[test]
     fn test_different_seeds_different_deals() {
         let mut gen1 = DealGenerator::new(1);
@@ -623,6 +1099,20 @@ mod tests {
         assert!(result.unwrap_err().contains("More than 13 cards"));
     }
 
+    #[test]
+    fn test_predeal_overfill_across_calls_error() {
+        let mut gen = DealGenerator::new(42);
+
+        // 10 cards, then 4 more to the same hand, exceeds 13 in total
+        let first: Vec<Card> = (0..10).map(|i| Card::from_index(i).unwrap()).collect();
+        let second: Vec<Card> = (10..14).map(|i| Card::from_index(i).unwrap()).collect();
+
+        gen.predeal(Position::East, &first).unwrap();
+        let result = gen.predeal(Position::East, &second);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("More than 13 cards"));
+    }
+
     #[test]
     fn test_predeal_no_duplicate_cards_in_deal() {
         let mut gen = DealGenerator::new(42);
@@ -687,6 +1177,174 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_restore_work_state_mid_stream() {
+        let mut gen1 = DealGenerator::new(1);
+        let all: Vec<Deal> = (0..50).map(|_| gen1.generate()).collect();
+
+        // Checkpoint after deal 25, going through the raw RNG parts
+        let mut gen2 = DealGenerator::new(1);
+        gen2.generate_many(25);
+        let snapshot = gen2.capture_work_state();
+        let (table, fptr, rptr) = snapshot.rng_state.parts();
+        let saved = DealWorkState {
+            rng_state: GnuRandomState::from_parts(table, fptr, rptr).unwrap(),
+            curdeal: snapshot.curdeal,
+        };
+
+        let mut resumed = DealGenerator::new(1);
+        resumed.restore_work_state(saved);
+        let rest = resumed.generate_many(25);
+        assert_eq!(rest, all[25..]);
+    }
+
+    #[test]
+    fn test_side_and_total_hcp() {
+        let mut gen = DealGenerator::new(1);
+        for deal in gen.generate_many(50) {
+            assert_eq!(deal.total_hcp(), 40);
+            assert_eq!(deal.side_hcp(Side::NS) + deal.side_hcp(Side::EW), 40);
+            assert_eq!(deal.side_hcp(Side::NS), deal.north.hcp() + deal.south.hcp());
+        }
+    }
+
+    #[test]
+    fn test_combined_suit_length() {
+        // N AKQT3.J6.KJ42.95, S J74.QT95.T.AK863
+        let deal = DealGenerator::new(1).generate();
+        assert_eq!(deal.combined_suit_length(Side::NS, Suit::Spades), 8);
+        assert_eq!(deal.combined_suit_length(Side::NS, Suit::Hearts), 6);
+        for suit in Suit::DESCENDING {
+            assert_eq!(
+                deal.combined_suit_length(Side::NS, suit)
+                    + deal.combined_suit_length(Side::EW, suit),
+                13
+            );
+        }
+    }
+
+    #[test]
+    fn test_to_pbn_string() {
+        let deal = DealGenerator::new(1).generate();
+        let north_first = deal.to_pbn_string(Position::North);
+        assert!(north_first.starts_with("N:AKQT3.J6.KJ42.95 "));
+        assert_eq!(north_first.split(' ').count(), 4);
+
+        // Leading from West puts north second
+        let west_first = deal.to_pbn_string(Position::West);
+        assert!(west_first.starts_with("W:"));
+        assert_eq!(west_first.split(' ').nth(1), Some("AKQT3.J6.KJ42.95"));
+    }
+
+    #[test]
+    fn test_swap_hands() {
+        let mut gen = DealGenerator::new(1);
+        let base = gen.generate();
+
+        let mut deal = base.clone();
+        deal.swap_hands(Position::East, Position::West);
+        assert_eq!(deal.east, base.west);
+        assert_eq!(deal.west, base.east);
+        assert_eq!(deal.north, base.north);
+        assert_eq!(deal.south, base.south);
+
+        deal.swap_hands(Position::West, Position::East);
+        assert_eq!(deal, base);
+        deal.swap_hands(Position::North, Position::North);
+        assert_eq!(deal, base);
+    }
+
+    #[test]
+    fn test_rotation_equivalence() {
+        let mut gen = DealGenerator::new(1);
+        let base = gen.generate();
+        let other = gen.generate();
+
+        let once = base.rotated(1);
+        assert_eq!(once.east, base.north);
+        assert_eq!(once.north, base.west);
+
+        let mut deal = base.clone();
+        for _ in 0..4 {
+            deal = deal.rotated(1);
+            assert!(deal.is_rotation_of(&base));
+            assert!(base.is_rotation_of(&deal));
+            assert_eq!(deal.canonical_key(true), base.canonical_key(true));
+        }
+        assert_eq!(deal, base);
+
+        // Card order within a hand does not matter
+        let mut sorted = once.clone();
+        sorted.sort_all_hands();
+        assert!(sorted.is_rotation_of(&base));
+        assert_eq!(sorted.canonical_key(false), once.canonical_key(false));
+
+        // Without rotation, a turned deal gets its own key
+        assert_ne!(once.canonical_key(false), base.canonical_key(false));
+
+        assert!(!other.is_rotation_of(&base));
+        assert_ne!(other.canonical_key(true), base.canonical_key(true));
+        assert_ne!(other.canonical_key(false), base.canonical_key(false));
+    }
+
+    #[test]
+    fn test_swapping_variants() {
+        let mut plain = DealGenerator::new(7);
+        let bases = plain.generate_many(2);
+
+        for (swapping, variants) in [(2u8, 2usize), (3, 6)] {
+            let mut gen = DealGenerator::new(7);
+            gen.set_swapping(swapping).unwrap();
+            let deals = gen.generate_many(2 * variants);
+
+            for (base, group) in bases.iter().zip(deals.chunks(variants)) {
+                // Each group starts with the plain shuffle
+                assert_eq!(&group[0], base);
+
+                for deal in group {
+                    // Same 52 cards, North unchanged, partitioned differently
+                    let masks: Vec<u64> = Position::ALL
+                        .iter()
+                        .map(|&p| deal.hand(p).card_mask())
+                        .collect();
+                    assert_eq!(masks.iter().fold(0, |acc, m| acc | m), (1u64 << 52) - 1);
+                    assert_eq!(deal.north, base.north);
+                    let mut others: Vec<u64> = masks[1..].to_vec();
+                    let mut base_others: Vec<u64> =
+                        [Position::East, Position::South, Position::West]
+                            .iter()
+                            .map(|&p| base.hand(p).card_mask())
+                            .collect();
+                    others.sort_unstable();
+                    base_others.sort_unstable();
+                    assert_eq!(others, base_others);
+                }
+
+                // Every variant is a distinct arrangement
+                for (i, a) in group.iter().enumerate() {
+                    assert!(group[i + 1..].iter().all(|b| a != b));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_swapping_rejects_predeal() {
+        let mut gen = DealGenerator::new(1);
+        assert!(gen.set_swapping(4).is_err());
+
+        gen.set_swapping(2).unwrap();
+        assert!(gen
+            .predeal(Position::North, &[Card::new(Suit::Spades, Rank::Ace)])
+            .is_err());
+
+        let mut gen = DealGenerator::new(1);
+        gen.predeal(Position::North, &[Card::new(Suit::Spades, Rank::Ace)])
+            .unwrap();
+        assert!(gen.set_swapping(3).is_err());
+        assert!(gen.set_swapping(0).is_ok());
+    }
+
     #[test]
     fn test_generator_state_with_predeal() {
         let mut gen1 = DealGenerator::new(42);
@@ -1,5 +1,5 @@
 use crate::shape::shape_to_index;
-use crate::{Card, Rank, Suit};
+use crate::{Card, CardParseError, Rank, RankExt, Suit, SuitExt};
 
 /// Represents a single player's hand of 13 cards
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -18,11 +18,88 @@ impl Hand {
         Hand { cards }
     }
 
+    /// Parse dot-separated S.H.D.C holdings, e.g. "AKQT3.J6.KJ42.95"
+    ///
+    /// An empty holding (or "-") is a void, so "AKQ..J6.KJ42" has no hearts.
+    /// Ranks are case-insensitive with ten written `T`. Partial hands are
+    /// accepted; more than 13 cards or a repeated card is an error.
+    pub fn from_pbn_suit_string(s: &str) -> Result<Hand, CardParseError> {
+        let holdings: Vec<&str> = s.split('.').collect();
+        if holdings.len() != 4 {
+            return Err(CardParseError {
+                message: format!(
+                    "Expected 4 suits separated by dots in \"{}\", got {}",
+                    s,
+                    holdings.len()
+                ),
+            });
+        }
+
+        let mut hand = Hand::new();
+        for (suit, holding) in Suit::DESCENDING.into_iter().zip(holdings) {
+            if holding == "-" {
+                continue;
+            }
+            for c in holding.chars() {
+                let Some(rank) = Rank::from_char(c) else {
+                    return Err(CardParseError {
+                        message: format!("Invalid rank '{}' in \"{}\"", c, s),
+                    });
+                };
+                let card = Card::new(suit, rank);
+                if hand.has_card(card) {
+                    return Err(CardParseError {
+                        message: format!("Card {}{} repeated in \"{}\"", c, suit.to_char(), s),
+                    });
+                }
+                if hand.len() == 13 {
+                    return Err(CardParseError {
+                        message: format!("Hand \"{}\" has more than 13 cards", s),
+                    });
+                }
+                hand.cards.push(card);
+            }
+        }
+        Ok(hand)
+    }
+
     /// Add a card to the hand
+    /// Debug builds assert that the hand does not grow past 13 cards
     pub fn add_card(&mut self, card: Card) {
+        debug_assert!(
+            self.cards.len() < 13,
+            "a hand cannot hold more than 13 cards"
+        );
         self.cards.push(card);
     }
 
+    /// Add several cards to the hand, with the same 13-card check as add_card()
+    pub fn add_cards(&mut self, cards: &[Card]) {
+        debug_assert!(
+            self.cards.len() + cards.len() <= 13,
+            "a hand cannot hold more than 13 cards"
+        );
+        self.cards.extend_from_slice(cards);
+    }
+
+    /// Remove a card from the hand, returning whether it was held
+    /// The remaining cards keep their order
+    pub fn remove_card(&mut self, card: Card) -> bool {
+        match self.cards.iter().position(|&c| c == card) {
+            Some(index) => {
+                self.cards.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Keep only the cards for which `f` returns true
+    /// The kept cards stay in their current order
+    pub fn retain<F: FnMut(&Card) -> bool>(&mut self, f: F) {
+        self.cards.retain(f);
+    }
+
     /// Get all cards in the hand
     pub fn cards(&self) -> &[Card] {
         &self.cards
@@ -53,20 +130,33 @@ impl Hand {
     }
 
     /// Calculate total High Card Points (HCP)
-    /// A=4, K=3, Q=2, J=1
+    /// A=4, K=3, Q=2, J=1, with no adjustment for shape or honour location.
+    /// The whole pack holds 40, so hand, side and deal totals all fit in a u8.
     pub fn hcp(&self) -> u8 {
         self.cards.iter().map(|c| c.hcp()).sum()
     }
 
+    /// Points as bridge-solver counts them for `guess_tricks`
+    ///
+    /// The solver's `points()` is the same 4/3/2/1 count, so this is `hcp()`
+    /// under the solver's name; the two never differ.
+    pub fn points(&self) -> u8 {
+        self.hcp()
+    }
+
+    /// Calculate High Card Points (HCP) in a specific suit
+    pub fn hcp_in_suit(&self, suit: Suit) -> u8 {
+        self.cards
+            .iter()
+            .filter(|c| c.suit == suit)
+            .map(|c| c.hcp())
+            .sum()
+    }
+
     /// Get the suit lengths in standard order [S, H, D, C]
     /// E.g., [5, 4, 3, 1] means 5 spades, 4 hearts, 3 diamonds, 1 club
     pub fn suit_lengths(&self) -> [usize; 4] {
-        [
-            self.suit_length(Suit::Spades),
-            self.suit_length(Suit::Hearts),
-            self.suit_length(Suit::Diamonds),
-            self.suit_length(Suit::Clubs),
-        ]
+        Suit::DESCENDING.map(|suit| self.suit_length(suit))
     }
 
     /// Get the distribution pattern as a sorted array [longest to shortest]
@@ -77,6 +167,34 @@ impl Hand {
         lengths
     }
 
+    /// The longest suit and its length; ties go to the higher-ranking suit
+    pub fn longest_suit(&self) -> (Suit, usize) {
+        self.pick_suit(|candidate, best| candidate > best)
+    }
+
+    /// The shortest suit and its length; ties go to the higher-ranking suit
+    pub fn shortest_suit(&self) -> (Suit, usize) {
+        self.pick_suit(|candidate, best| candidate < best)
+    }
+
+    /// Scan suits from spades down, keeping a suit only if it strictly beats
+    /// the current pick, so ties stay with the higher-ranking suit
+    fn pick_suit(&self, beats: impl Fn(usize, usize) -> bool) -> (Suit, usize) {
+        [Suit::Hearts, Suit::Diamonds, Suit::Clubs]
+            .into_iter()
+            .fold(
+                (Suit::Spades, self.suit_length(Suit::Spades)),
+                |best, suit| {
+                    let length = self.suit_length(suit);
+                    if beats(length, best.1) {
+                        (suit, length)
+                    } else {
+                        best
+                    }
+                },
+            )
+    }
+
     /// Get the shape index (0-559) for O(1) shape mask matching.
     ///
     /// This index uniquely identifies the hand's ordered shape (S-H-D-C).
@@ -92,12 +210,27 @@ impl Hand {
         format!("{}-{}-{}-{}", dist[0], dist[1], dist[2], dist[3])
     }
 
+    /// Get the exact suit pattern as "S=H=D=C" lengths (e.g., "5=3=2=3")
+    /// Unlike shape(), the lengths stay in suit order rather than being sorted
+    pub fn pattern_string(&self) -> String {
+        let lengths = self.suit_lengths();
+        format!(
+            "{}={}={}={}",
+            lengths[0], lengths[1], lengths[2], lengths[3]
+        )
+    }
+
     /// Check if hand is balanced (4-3-3-3, 4-4-3-2, or 5-3-3-2)
     pub fn is_balanced(&self) -> bool {
         let dist = self.distribution();
         matches!(dist, [4, 3, 3, 3] | [4, 4, 3, 2] | [5, 3, 3, 2])
     }
 
+    /// Check if hand is semi-balanced: balanced, 5-4-2-2 or 6-3-2-2
+    pub fn is_semi_balanced(&self) -> bool {
+        self.is_balanced() || matches!(self.distribution(), [5, 4, 2, 2] | [6, 3, 2, 2])
+    }
+
     /// Count controls (A=2, K=1)
     pub fn controls(&self) -> u8 {
         self.cards
@@ -110,6 +243,19 @@ impl Hand {
             .sum()
     }
 
+    /// Count controls (A=2, K=1) in a specific suit
+    pub fn controls_in_suit(&self, suit: Suit) -> u8 {
+        self.cards
+            .iter()
+            .filter(|c| c.suit == suit)
+            .map(|c| match c.rank {
+                Rank::Ace => 2,
+                Rank::King => 1,
+                _ => 0,
+            })
+            .sum()
+    }
+
     /// Count honors (A, K, Q, J, T) in a specific suit
     pub fn honors_in_suit(&self, suit: Suit) -> u8 {
         self.cards
@@ -118,6 +264,40 @@ impl Hand {
             .count() as u8
     }
 
+    /// Bitmask of the honors held in a suit: A=16, K=8, Q=4, J=2, T=1
+    pub fn honors_mask(&self, suit: Suit) -> u8 {
+        self.cards
+            .iter()
+            .filter(|c| c.suit == suit && c.rank >= Rank::Ten)
+            .map(|c| 1 << (c.rank as u8 - Rank::Ten as u8))
+            .fold(0, |mask, bit| mask | bit)
+    }
+
+    /// Calculate HCP adjusted for unguarded honors, in halves to stay integer
+    /// Starts from raw HCP and deducts per suit:
+    /// - Singleton K, Q or J: -1
+    /// - Doubleton headed by Q or J (Qx, QJ, Jx): -0.5
+    ///
+    /// So 12 HCP with a singleton king returns 22 (11 points).
+    pub fn hcp_adjusted(&self) -> i32 {
+        const KING: u8 = 8;
+        const QUEEN: u8 = 4;
+        const JACK: u8 = 2;
+
+        let deductions: i32 = Suit::ALL
+            .iter()
+            .map(|&suit| {
+                let top = self.honors_mask(suit) & !1; // Tens do not matter here
+                match self.suit_length(suit) {
+                    1 if top & (KING | QUEEN | JACK) != 0 => 2,
+                    2 if top != 0 && top <= QUEEN | JACK => 1,
+                    _ => 0,
+                }
+            })
+            .sum();
+        self.hcp() as i32 * 2 - deductions
+    }
+
     /// Sort the hand by suit (spades first) and rank (high to low)
     pub fn sort(&mut self) {
         self.cards.sort_by(|a, b| {
@@ -164,6 +344,16 @@ impl Hand {
         true
     }
 
+    /// Check if hand matches a range shape pattern (S-H-D-C order)
+    /// Each suit is (min, max) with None meaning unbounded
+    /// E.g., [(Some(5), None), (Some(4), None), (None, None), (None, None)] matches 5+ spades and 4+ hearts
+    pub fn matches_range_shape(&self, pattern: &[(Option<u8>, Option<u8>); 4]) -> bool {
+        let lengths = self.suit_lengths();
+        lengths.iter().zip(pattern).all(|(&len, &(min, max))| {
+            min.is_none_or(|m| len >= m as usize) && max.is_none_or(|m| len <= m as usize)
+        })
+    }
+
     /// Check if hand matches a distribution pattern (suit-order independent)
     /// E.g., [4, 3, 3, 3] matches any hand with one 4-card suit and three 3-card suits
     pub fn matches_distribution(&self, pattern: &[u8; 4]) -> bool {
@@ -186,6 +376,27 @@ impl Hand {
             + self.losers_in_suit(Suit::Clubs)
     }
 
+    /// Calculate losers allowing for partner's cover cards
+    ///
+    /// Same count as losers(), except that a missing A, K or Q held by partner
+    /// no longer counts: xxx opposite AKx is one loser rather than three.
+    pub fn losers_covered(&self, partner: &Hand) -> u8 {
+        Suit::ALL
+            .iter()
+            .map(|&suit| {
+                // The honors losers_in_suit() checks: A, AK or AKQ by length
+                let checked = self.suit_length(suit).min(3);
+                [Rank::Ace, Rank::King, Rank::Queen][..checked]
+                    .iter()
+                    .filter(|&&rank| {
+                        let card = Card::new(suit, rank);
+                        !self.has_card(card) && !partner.has_card(card)
+                    })
+                    .count() as u8
+            })
+            .sum()
+    }
+
     /// Calculate losers in a specific suit
     /// Rules:
     /// - Void: 0 losers
@@ -239,11 +450,46 @@ impl Hand {
         }
     }
 
+    /// Calculate New Losing Trick Count losers, in quarters to stay integer
+    /// (so 7.5 losers returns 30)
+    pub fn new_losers(&self) -> u8 {
+        Suit::ALL.iter().map(|&s| self.new_losers_in_suit(s)).sum()
+    }
+
+    /// Calculate NLTC losers in a specific suit, in quarters
+    /// Rules:
+    /// - A missing ace costs 1.5 losers (6), a king 1 (4), a queen 0.5 (2)
+    /// - 3+ cards: A, K and Q count (at most 3 losers)
+    /// - Doubleton: only A and K count (at most 2.5 losers)
+    /// - Singleton: only the A counts (at most 1.5 losers)
+    /// - Void: 0 losers
+    pub fn new_losers_in_suit(&self, suit: Suit) -> u8 {
+        let has = |rank| self.has_card(Card::new(suit, rank));
+        let counted: &[(Rank, u8)] = match self.suit_length(suit) {
+            0 => return 0,
+            1 => &[(Rank::Ace, 6)],
+            2 => &[(Rank::Ace, 6), (Rank::King, 4)],
+            _ => &[(Rank::Ace, 6), (Rank::King, 4), (Rank::Queen, 2)],
+        };
+        counted
+            .iter()
+            .filter(|&&(rank, _)| !has(rank))
+            .map(|&(_, quarters)| quarters)
+            .sum()
+    }
+
     /// Check if hand contains a specific card
     pub fn has_card(&self, card: Card) -> bool {
         self.cards.contains(&card)
     }
 
+    /// Get the hand as a 52-bit mask, with bit `card.to_index()` set for each card
+    pub fn card_mask(&self) -> u64 {
+        self.cards
+            .iter()
+            .fold(0u64, |mask, c| mask | (1u64 << c.to_index()))
+    }
+
     /// Count number of tens in hand
     pub fn tens(&self) -> u8 {
         self.cards.iter().filter(|c| c.rank == Rank::Ten).count() as u8
@@ -319,10 +565,7 @@ impl Hand {
 
     /// Count top 2 honors (A, K) in specific suit
     pub fn top2_in_suit(&self, suit: Suit) -> u8 {
-        self.cards
-            .iter()
-            .filter(|c| c.suit == suit && matches!(c.rank, Rank::Ace | Rank::King))
-            .count() as u8
+        self.top_n_in_suit(suit, 2)
     }
 
     /// Count top 3 honors (A, K, Q) in hand
@@ -335,10 +578,7 @@ impl Hand {
 
     /// Count top 3 honors (A, K, Q) in specific suit
     pub fn top3_in_suit(&self, suit: Suit) -> u8 {
-        self.cards
-            .iter()
-            .filter(|c| c.suit == suit && matches!(c.rank, Rank::Ace | Rank::King | Rank::Queen))
-            .count() as u8
+        self.top_n_in_suit(suit, 3)
     }
 
     /// Count top 4 honors (A, K, Q, J) in hand
@@ -351,13 +591,7 @@ impl Hand {
 
     /// Count top 4 honors (A, K, Q, J) in specific suit
     pub fn top4_in_suit(&self, suit: Suit) -> u8 {
-        self.cards
-            .iter()
-            .filter(|c| {
-                c.suit == suit
-                    && matches!(c.rank, Rank::Ace | Rank::King | Rank::Queen | Rank::Jack)
-            })
-            .count() as u8
+        self.top_n_in_suit(suit, 4)
     }
 
     /// Count top 5 honors (A, K, Q, J, T) in hand
@@ -375,15 +609,23 @@ impl Hand {
 
     /// Count top 5 honors (A, K, Q, J, T) in specific suit
     pub fn top5_in_suit(&self, suit: Suit) -> u8 {
+        self.top_n_in_suit(suit, 5)
+    }
+
+    /// Count how many of the top `n` ranks of a suit are held
+    /// (n = 3 counts A, K, Q; n of 13 or more counts the whole suit)
+    pub fn top_n_in_suit(&self, suit: Suit, n: u8) -> u8 {
         self.cards
             .iter()
-            .filter(|c| {
-                c.suit == suit
-                    && matches!(
-                        c.rank,
-                        Rank::Ace | Rank::King | Rank::Queen | Rank::Jack | Rank::Ten
-                    )
-            })
+            .filter(|c| c.suit == suit && (Rank::Ace as u8 - c.rank as u8) < n)
+            .count() as u8
+    }
+
+    /// Count intermediate spot cards (T, 9, 8) in a suit
+    pub fn intermediates_in_suit(&self, suit: Suit) -> u8 {
+        self.cards
+            .iter()
+            .filter(|c| c.suit == suit && matches!(c.rank, Rank::Ten | Rank::Nine | Rank::Eight))
             .count() as u8
     }
 
@@ -416,6 +658,59 @@ impl Hand {
             .sum()
     }
 
+    /// Calculate quick tricks, in halves to stay integer
+    /// AK=4, AQ=3, A or KQ=2, Kx=1 (so 2.5 quick tricks returns 5)
+    pub fn quick_tricks(&self) -> u8 {
+        Suit::ALL
+            .iter()
+            .map(|&s| self.quick_tricks_in_suit(s))
+            .sum()
+    }
+
+    /// Calculate quick tricks in specific suit, in halves
+    pub fn quick_tricks_in_suit(&self, suit: Suit) -> u8 {
+        let has = |rank| self.has_card(Card::new(suit, rank));
+        match (has(Rank::Ace), has(Rank::King), has(Rank::Queen)) {
+            (true, true, _) => 4,
+            (true, false, true) => 3,
+            (true, false, false) => 2,
+            (false, true, true) => 2,
+            (false, true, false) if self.suit_length(suit) >= 2 => 1,
+            _ => 0,
+        }
+    }
+
+    /// Calculate Zar points (Zar Petkov)
+    /// HCP + controls (A=2, K=1) + distribution, where distribution is the sum of
+    /// the two longest suits plus the difference between the longest and shortest.
+    /// 26 Zar points is the usual opening requirement.
+    pub fn zar_points(&self) -> u8 {
+        let dist = self.distribution();
+        let distribution = dist[0] + dist[1] + (dist[0] - dist[3]);
+        self.hcp() + self.controls() + distribution as u8
+    }
+
+    /// Shortness points: 3 per void, 2 per singleton, 1 per doubleton
+    pub fn shortness_points(&self) -> u8 {
+        self.suit_lengths()
+            .iter()
+            .map(|&length| match length {
+                0 => 3,
+                1 => 2,
+                2 => 1,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Length points: 1 for each card beyond four in a suit
+    pub fn length_points(&self) -> u8 {
+        self.suit_lengths()
+            .iter()
+            .map(|&length| length.saturating_sub(4) as u8)
+            .sum()
+    }
+
     /// Calculate suit quality metric (Bridge World Oct 1982)
     /// Returns quality value multiplied by 100 to use integer math
     pub fn suit_quality(&self, suit: Suit) -> i32 {
@@ -502,7 +797,7 @@ impl Hand {
         let mut shape_points = 0;
 
         // Evaluate each suit
-        for suit in [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs] {
+        for suit in Suit::DESCENDING {
             let mut cards: Vec<Card> = self
                 .cards
                 .iter()
@@ -610,6 +905,234 @@ impl Default for Hand {
 mod tests {
     use super::*;
 
+    /// Build a hand from PBN-style holdings, e.g. "AKQT3.J6.KJ42.95"
+    fn parse_hand(s: &str) -> Hand {
+        Hand::from_pbn_suit_string(s).unwrap()
+    }
+
+    #[test]
+    fn test_matches_range_shape() {
+        // 5 spades, 4 hearts, 3 diamonds, 1 club
+        let cards = [
+            (Suit::Spades, Rank::Ace),
+            (Suit::Spades, Rank::King),
+            (Suit::Spades, Rank::Five),
+            (Suit::Spades, Rank::Four),
+            (Suit::Spades, Rank::Three),
+            (Suit::Hearts, Rank::Ace),
+            (Suit::Hearts, Rank::Six),
+            (Suit::Hearts, Rank::Five),
+            (Suit::Hearts, Rank::Four),
+            (Suit::Diamonds, Rank::Three),
+            (Suit::Diamonds, Rank::Two),
+            (Suit::Diamonds, Rank::Four),
+            (Suit::Clubs, Rank::Two),
+        ];
+        let hand = Hand::from_cards(cards.iter().map(|&(s, r)| Card::new(s, r)).collect());
+
+        let any = (None, None);
+        assert!(hand.matches_range_shape(&[(Some(5), None), (Some(4), None), any, any]));
+        assert!(!hand.matches_range_shape(&[(Some(6), None), any, any, any]));
+        assert!(hand.matches_range_shape(&[any, any, (Some(2), Some(4)), any]));
+        assert!(!hand.matches_range_shape(&[any, any, any, (Some(2), Some(4))]));
+    }
+
+    #[test]
+    fn test_new_losers() {
+        let suit_hand = |ranks: &[Rank]| {
+            Hand::from_cards(ranks.iter().map(|&r| Card::new(Suit::Spades, r)).collect())
+        };
+
+        // AQ doubleton: the queen does not count, missing king = 1 loser
+        assert_eq!(
+            suit_hand(&[Rank::Ace, Rank::Queen]).new_losers_in_suit(Suit::Spades),
+            4
+        );
+        // KQ doubleton: missing ace = 1.5 losers
+        assert_eq!(
+            suit_hand(&[Rank::King, Rank::Queen]).new_losers_in_suit(Suit::Spades),
+            6
+        );
+        // Axx: missing king and queen = 1.5 losers
+        assert_eq!(
+            suit_hand(&[Rank::Ace, Rank::Seven, Rank::Two]).new_losers_in_suit(Suit::Spades),
+            6
+        );
+        // xxx = 3 losers, singleton x = 1.5, singleton A = 0
+        assert_eq!(
+            suit_hand(&[Rank::Nine, Rank::Seven, Rank::Two]).new_losers_in_suit(Suit::Spades),
+            12
+        );
+        assert_eq!(suit_hand(&[Rank::Nine]).new_losers_in_suit(Suit::Spades), 6);
+        assert_eq!(suit_hand(&[Rank::Ace]).new_losers_in_suit(Suit::Spades), 0);
+        // Void
+        assert_eq!(suit_hand(&[Rank::Ace]).new_losers_in_suit(Suit::Hearts), 0);
+
+        // Whole hand: AKQT3.J6.KJ42.95 = 0 + 2.5 + 2 + 2.5 = 7 losers
+        let mut gen = crate::DealGenerator::new(1);
+        let deal = gen.generate();
+        assert_eq!(deal.hand(crate::Position::North).new_losers(), 28);
+    }
+
+    #[test]
+    fn test_zar_points() {
+        // Petkov's distribution count, (a + b) + (a - d) for suit lengths
+        // a >= b >= c >= d, on honourless hands of each shape
+        for (hand, points) in [
+            ("T987.T98.T98.T98", 8),  // 4-3-3-3
+            ("T987.T987.T98.T9", 10), // 4-4-3-2
+            ("T9876.T98.T98.T9", 11), // 5-3-3-2
+            ("T9876.T987.T98.T", 13), // 5-4-3-1
+            ("T9876.T9876.T9.T", 14), // 5-5-2-1
+            ("T98765.T987.T9.T", 15), // 6-4-2-1
+            ("T987654.T98765..", 20), // 7-6-0-0
+        ] {
+            assert_eq!(parse_hand(hand).zar_points(), points, "{}", hand);
+        }
+
+        // Zar high-card points: HCP plus controls, so A=6, K=4, Q=2, J=1
+        for (hand, points) in [
+            ("A987.T98.T98.T98", 6),
+            ("K987.T98.T98.T98", 4),
+            ("Q987.T98.T98.T98", 2),
+            ("J987.T98.T98.T98", 1),
+        ] {
+            assert_eq!(parse_hand(hand).zar_points(), 8 + points, "{}", hand);
+        }
+
+        // The opening line is 26: a 5-4-3-1 (13) needs 13 Zar high-card points
+        assert_eq!(parse_hand("AK876.QJ87.T98.T").zar_points(), 26);
+        assert_eq!(parse_hand("AK876.Q987.T98.T").zar_points(), 25);
+
+        // Seed 1 north AKQT3.J6.KJ42.95: 18 + (5+4) + (5-2) = 30
+        let mut gen = crate::DealGenerator::new(1);
+        let deal = gen.generate();
+        assert_eq!(deal.hand(crate::Position::North).zar_points(), 30);
+    }
+
+    #[test]
+    fn test_distribution_points() {
+        // 5-4-3-1: the singleton is 2 shortness points, the fifth spade 1 length point
+        let hand = parse_hand("AK742.QJ85.K43.9");
+        assert_eq!(hand.shortness_points(), 2);
+        assert_eq!(hand.length_points(), 1);
+
+        // 7-6-0-0: two voids, and 3 + 2 cards beyond four
+        let hand = parse_hand("AKQJ987.T98765..");
+        assert_eq!(hand.shortness_points(), 6);
+        assert_eq!(hand.length_points(), 5);
+
+        // 4-3-3-3 has neither
+        let hand = parse_hand("AJ74.K62.Q53.J82");
+        assert_eq!(hand.shortness_points(), 0);
+        assert_eq!(hand.length_points(), 0);
+    }
+
+    #[test]
+    fn test_longest_and_shortest_suit() {
+        // 6-4-2-1
+        let hand = parse_hand("A2.KQJ987.T.8765");
+        assert_eq!(hand.longest_suit(), (Suit::Hearts, 6));
+        assert_eq!(hand.shortest_suit(), (Suit::Diamonds, 1));
+
+        // 4-4-3-2 in H-C-S-D: the tied fours go to hearts
+        let hand = parse_hand("AK2.QJ98.T7.8765");
+        assert_eq!(hand.longest_suit(), (Suit::Hearts, 4));
+        assert_eq!(hand.shortest_suit(), (Suit::Diamonds, 2));
+
+        // 4-3-3-3: the tied threes go to the higher suit
+        let hand = parse_hand("AK2.QJ9.T87.8765");
+        assert_eq!(hand.longest_suit(), (Suit::Clubs, 4));
+        assert_eq!(hand.shortest_suit(), (Suit::Spades, 3));
+    }
+
+    #[test]
+    fn test_hcp_adjusted() {
+        let hand = parse_hand("AKT42.QJ5.K83.J6");
+        assert_eq!(hand.honors_mask(Suit::Spades), 16 | 8 | 1);
+        assert_eq!(hand.honors_mask(Suit::Hearts), 4 | 2);
+        assert_eq!(hand.honors_mask(Suit::Clubs), 2);
+        // Only the Jx doubleton is docked: 14 HCP - 0.5
+        assert_eq!(hand.hcp(), 14);
+        assert_eq!(hand.hcp_adjusted(), 27);
+
+        // No short honors: unchanged
+        assert_eq!(parse_hand("AK42.Q53.K83.J62").hcp_adjusted(), 26);
+        // Singleton K and singleton Q: 14 HCP - 2
+        assert_eq!(parse_hand("K.Q.AJ9632.AT532").hcp_adjusted(), 24);
+        // Qx and QJ doubletons: -0.5 each; Kx and Ax are fine
+        assert_eq!(parse_hand("Q4.QJ.K9732.A653").hcp_adjusted(), 2 * 12 - 2);
+        // Singleton ace is not docked
+        assert_eq!(parse_hand("A.K8.QJ953.98632").hcp_adjusted(), 2 * 10);
+    }
+
+    #[test]
+    fn test_quick_tricks() {
+        let suit_hand = |ranks: &[Rank]| {
+            Hand::from_cards(ranks.iter().map(|&r| Card::new(Suit::Spades, r)).collect())
+        };
+
+        assert_eq!(
+            suit_hand(&[Rank::Ace, Rank::King, Rank::Queen]).quick_tricks_in_suit(Suit::Spades),
+            4
+        );
+        assert_eq!(
+            suit_hand(&[Rank::Ace, Rank::Queen]).quick_tricks_in_suit(Suit::Spades),
+            3
+        );
+        assert_eq!(
+            suit_hand(&[Rank::King, Rank::Queen]).quick_tricks_in_suit(Suit::Spades),
+            2
+        );
+        assert_eq!(
+            suit_hand(&[Rank::King, Rank::Four]).quick_tricks_in_suit(Suit::Spades),
+            1
+        );
+        assert_eq!(
+            suit_hand(&[Rank::Ace, Rank::Four]).quick_tricks_in_suit(Suit::Spades),
+            2
+        );
+        assert_eq!(
+            suit_hand(&[Rank::Ace]).quick_tricks_in_suit(Suit::Spades),
+            2
+        );
+        // Singleton king is not a quick trick
+        assert_eq!(
+            suit_hand(&[Rank::King]).quick_tricks_in_suit(Suit::Spades),
+            0
+        );
+
+        // Whole hand: AK in spades (4) + Kx in hearts (1)
+        let hand = Hand::from_cards(vec![
+            Card::new(Suit::Spades, Rank::Ace),
+            Card::new(Suit::Spades, Rank::King),
+            Card::new(Suit::Hearts, Rank::King),
+            Card::new(Suit::Hearts, Rank::Two),
+            Card::new(Suit::Diamonds, Rank::Queen),
+        ]);
+        assert_eq!(hand.quick_tricks(), 5);
+    }
+
+    #[test]
+    fn test_card_mask() {
+        let mut hand = Hand::new();
+        hand.add_card(Card::new(Suit::Clubs, Rank::Two)); // index 0
+        hand.add_card(Card::new(Suit::Spades, Rank::Ace)); // index 51
+        assert_eq!(hand.card_mask(), 1 | (1u64 << 51));
+
+        // The four hands of a deal partition the 52 cards
+        let mut gen = crate::DealGenerator::new(1);
+        let deal = gen.generate();
+        let mut all = 0u64;
+        for pos in crate::Position::ALL {
+            let mask = deal.hand(pos).card_mask();
+            assert_eq!(mask.count_ones(), 13);
+            assert_eq!(all & mask, 0);
+            all |= mask;
+        }
+        assert_eq!(all, (1u64 << 52) - 1);
+    }
+
     #[test]
     fn test_hcp_calculation() {
         let mut hand = Hand::new();
@@ -622,6 +1145,38 @@ mod tests {
         assert_eq!(hand.hcp(), 10);
     }
 
+    #[test]
+    fn test_points_matches_hcp() {
+        let mut hand = Hand::new();
+        hand.add_card(Card::new(Suit::Spades, Rank::Ace)); // 4
+        hand.add_card(Card::new(Suit::Spades, Rank::King)); // 3
+        hand.add_card(Card::new(Suit::Hearts, Rank::Queen)); // 2
+        hand.add_card(Card::new(Suit::Clubs, Rank::Jack)); // 1
+        hand.add_card(Card::new(Suit::Clubs, Rank::Ten)); // 0
+
+        assert_eq!(hand.points(), 10);
+        assert_eq!(Hand::new().points(), 0);
+
+        let deal = crate::DealGenerator::new(1).generate();
+        for position in crate::Position::ALL {
+            assert_eq!(deal.hand(position).points(), deal.hand(position).hcp());
+        }
+    }
+
+    #[test]
+    fn test_hcp_in_suit() {
+        let mut hand = Hand::new();
+        hand.add_card(Card::new(Suit::Spades, Rank::Ace)); // 4
+        hand.add_card(Card::new(Suit::Spades, Rank::Jack)); // 1
+        hand.add_card(Card::new(Suit::Hearts, Rank::King)); // 3
+        hand.add_card(Card::new(Suit::Clubs, Rank::Seven)); // 0
+
+        assert_eq!(hand.hcp_in_suit(Suit::Spades), 5);
+        assert_eq!(hand.hcp_in_suit(Suit::Hearts), 3);
+        assert_eq!(hand.hcp_in_suit(Suit::Diamonds), 0);
+        assert_eq!(hand.hcp_in_suit(Suit::Clubs), 0);
+    }
+
     #[test]
     fn test_suit_length() {
         let mut hand = Hand::new();
@@ -658,6 +1213,174 @@ mod tests {
         assert_eq!(hand.distribution(), [4, 3, 3, 3]);
     }
 
+    #[test]
+    fn test_semi_balanced_hand() {
+        let flat = parse_hand("AK32.Q54.J87.T96");
+        assert!(flat.is_balanced());
+        assert!(flat.is_semi_balanced());
+
+        let five_four = parse_hand("AK432.Q543.J8.T9");
+        assert!(!five_four.is_balanced());
+        assert!(five_four.is_semi_balanced());
+
+        let seven_three = parse_hand("AKQ5432.Q54.J8.T");
+        assert!(!seven_three.is_balanced());
+        assert!(!seven_three.is_semi_balanced());
+    }
+
+    #[test]
+    fn test_top_n_in_suit() {
+        let hand = parse_hand("AKQT3.J6.KJ42.95");
+
+        assert_eq!(hand.top_n_in_suit(Suit::Spades, 3), 3);
+        assert_eq!(hand.top_n_in_suit(Suit::Spades, 5), 4);
+        assert_eq!(hand.top_n_in_suit(Suit::Diamonds, 3), 1);
+        assert_eq!(hand.top_n_in_suit(Suit::Diamonds, 5), 2);
+        assert_eq!(hand.top_n_in_suit(Suit::Spades, 0), 0);
+        assert_eq!(hand.top_n_in_suit(Suit::Spades, 13), 5);
+        assert_eq!(hand.top_n_in_suit(Suit::Spades, 20), 5);
+
+        for suit in Suit::ALL {
+            assert_eq!(hand.top_n_in_suit(suit, 3), hand.top3_in_suit(suit));
+            assert_eq!(hand.top_n_in_suit(suit, 5), hand.top5_in_suit(suit));
+        }
+    }
+
+    #[test]
+    fn test_losers_covered() {
+        let hand = parse_hand("432.AK2.AQ32.K32");
+        assert_eq!(hand.losers(), 7);
+
+        // Three small spades opposite AKx: only the queen is still missing
+        let partner = parse_hand("AK5.543.654.6543");
+        assert_eq!(hand.losers_covered(&partner), 5);
+
+        // Partner's honors cover the missing K, Q of diamonds and A, Q of clubs too
+        let partner = parse_hand("AKQ.543.K5.AQ654");
+        assert_eq!(hand.losers_covered(&partner), 1);
+
+        // Nothing covered: same as plain losers
+        let partner = parse_hand("765.JT9.JT9.JT98");
+        assert_eq!(hand.losers_covered(&partner), hand.losers());
+    }
+
+    #[test]
+    fn test_pattern_string() {
+        let hand = parse_hand("AKQT3.J65.42.954");
+        assert_eq!(hand.shape(), "5-3-3-2");
+        assert_eq!(hand.pattern_string(), "5=3=2=3");
+
+        let void = parse_hand(".AKQJT9876.5432.");
+        assert_eq!(void.shape(), "9-4-0-0");
+        assert_eq!(void.pattern_string(), "0=9=4=0");
+    }
+
+    #[test]
+    fn test_add_cards() {
+        let mut hand = Hand::new();
+        hand.add_card(Card::new(Suit::Spades, Rank::Ace));
+        hand.add_cards(&[
+            Card::new(Suit::Hearts, Rank::King),
+            Card::new(Suit::Clubs, Rank::Two),
+        ]);
+        assert_eq!(hand.len(), 3);
+        assert!(hand.has_card(Card::new(Suit::Hearts, Rank::King)));
+        assert!(hand.has_card(Card::new(Suit::Clubs, Rank::Two)));
+
+        let full: Vec<Card> = (0..13).map(|i| Card::from_index(i).unwrap()).collect();
+        let mut hand = Hand::new();
+        hand.add_cards(&full);
+        assert_eq!(hand.len(), 13);
+    }
+
+    #[test]
+    fn test_remove_card() {
+        let mut hand = parse_hand("AKQT3.J6.KJ42.95");
+        let before = hand.cards().to_vec();
+
+        let jack = Card::new(Suit::Hearts, Rank::Jack);
+        assert!(hand.remove_card(jack));
+        assert_eq!(hand.len(), 12);
+        assert!(!hand.has_card(jack));
+        let expected: Vec<Card> = before.iter().copied().filter(|&c| c != jack).collect();
+        assert_eq!(hand.cards(), &expected[..]);
+
+        // Not held (any more): nothing changes
+        assert!(!hand.remove_card(jack));
+        assert!(!hand.remove_card(Card::new(Suit::Clubs, Rank::Ace)));
+        assert_eq!(hand.cards(), &expected[..]);
+    }
+
+    #[test]
+    fn test_retain_then_sort() {
+        let mut hand = parse_hand("AKQT3.J6.KJ42.95");
+        hand.retain(|c| c.suit == Suit::Spades);
+        assert_eq!(hand.len(), 5);
+        assert!(hand.cards().iter().all(|c| c.suit == Suit::Spades));
+
+        // Discard down to the black suits, then sort: spades then clubs, high to low
+        let mut hand = Hand::from_cards(vec![
+            Card::new(Suit::Clubs, Rank::Five),
+            Card::new(Suit::Hearts, Rank::Ace),
+            Card::new(Suit::Spades, Rank::Three),
+            Card::new(Suit::Clubs, Rank::Nine),
+            Card::new(Suit::Spades, Rank::King),
+        ]);
+        hand.retain(|c| c.suit != Suit::Hearts);
+        hand.sort();
+        assert_eq!(
+            hand.cards(),
+            &[
+                Card::new(Suit::Spades, Rank::King),
+                Card::new(Suit::Spades, Rank::Three),
+                Card::new(Suit::Clubs, Rank::Nine),
+                Card::new(Suit::Clubs, Rank::Five),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_pbn_suit_string() {
+        let hand = Hand::from_pbn_suit_string("AKQT3.J6.KJ42.95").unwrap();
+        assert_eq!(hand.len(), 13);
+        assert_eq!(hand.suit_lengths(), [5, 2, 4, 2]);
+        assert_eq!(hand.hcp(), 14);
+        assert!(hand.has_card(Card::new(Suit::Spades, Rank::Ten)));
+        assert!(hand.has_card(Card::new(Suit::Clubs, Rank::Five)));
+
+        // Consecutive dots and "-" are voids; ranks are case-insensitive
+        let void = Hand::from_pbn_suit_string("akq76..QJ98.T987").unwrap();
+        assert_eq!(void.suit_lengths(), [5, 0, 4, 4]);
+        assert_eq!(void.hcp(), 12);
+        let dash = Hand::from_pbn_suit_string("AKQ76.-.QJ98.T987").unwrap();
+        assert_eq!(dash.card_mask(), void.card_mask());
+    }
+
+    #[test]
+    fn test_from_pbn_suit_string_rejects_bad_input() {
+        let err = Hand::from_pbn_suit_string("AKQ1.J6.KJ42.95").unwrap_err();
+        assert!(err.message.contains("'1'"), "{}", err);
+        assert!(Hand::from_pbn_suit_string("AKQ.J6.KJ42").is_err());
+        assert!(Hand::from_pbn_suit_string("AKA.J6.KJ42.95").is_err());
+        assert!(Hand::from_pbn_suit_string("AKQJT98765432.A..").is_err());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "more than 13 cards")]
+    fn test_add_card_past_thirteen() {
+        let mut hand = parse_hand("AKQT3.J6.KJ42.95");
+        hand.add_card(Card::new(Suit::Clubs, Rank::Two));
+    }
+
+    #[test]
+    fn test_intermediates_in_suit() {
+        let hand = parse_hand("QJT98.QJ432.A.K2");
+        assert_eq!(hand.intermediates_in_suit(Suit::Spades), 3);
+        assert_eq!(hand.intermediates_in_suit(Suit::Hearts), 0);
+        assert_eq!(hand.intermediates_in_suit(Suit::Diamonds), 0);
+    }
+
     #[test]
     fn test_controls() {
         let mut hand = Hand::new();
@@ -666,5 +1389,20 @@ mod tests {
         hand.add_card(Card::new(Suit::Diamonds, Rank::Ace)); // 2
 
         assert_eq!(hand.controls(), 5);
+        assert_eq!(hand.controls_in_suit(Suit::Spades), 2);
+        assert_eq!(hand.controls_in_suit(Suit::Hearts), 1);
+        assert_eq!(hand.controls_in_suit(Suit::Clubs), 0);
+
+        // Per-suit controls add up to the whole hand
+        let mut gen = crate::DealGenerator::new(1);
+        for deal in gen.generate_many(20) {
+            for hand in [&deal.north, &deal.east, &deal.south, &deal.west] {
+                let by_suit: u8 = Suit::DESCENDING
+                    .iter()
+                    .map(|&suit| hand.controls_in_suit(suit))
+                    .sum();
+                assert_eq!(by_suit, hand.controls());
+            }
+        }
     }
 }
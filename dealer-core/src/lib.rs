@@ -1,8 +1,13 @@
+mod card;
 mod convert;
 mod deal;
 mod fast_deal;
 mod hand;
+mod position;
+mod rank;
 mod shape;
+mod suit;
+mod vulnerability;
 
 // Re-export core types from bridge-types
 pub use bridge_types::{Card, Direction, Rank, Suit};
@@ -10,9 +15,14 @@ pub use bridge_types::{Card, Direction, Rank, Suit};
 // Position is an alias for Direction for backwards compatibility
 pub type Position = Direction;
 
+pub use card::{parse_card, CardExt, CardParseError};
 pub use deal::{Deal, DealGenerator, DealGeneratorConfig, DealGeneratorState, DealWorkState};
 pub use fast_deal::{
     generate_deal_from_seed, generate_deal_from_seed_no_predeal, FastDealConfig, FastDealGenerator,
 };
 pub use hand::Hand;
+pub use position::{PositionExt, Side};
+pub use rank::RankExt;
 pub use shape::{shape_to_index, ShapeMask};
+pub use suit::SuitExt;
+pub use vulnerability::Vulnerability;
@@ -0,0 +1,98 @@
+//! Seat rotation helpers and partnerships
+//!
+//! `Position` is bridge-types' `Direction`, so these are provided through an
+//! extension trait rather than inherent methods. `partner()` comes from
+//! bridge-types itself.
+
+use crate::Position;
+
+/// Clockwise rotation for positions (North, East, South, West)
+pub trait PositionExt {
+    /// The next position clockwise (the left-hand opponent)
+    fn next(self) -> Position;
+    /// The previous position clockwise (the right-hand opponent)
+    fn prev(self) -> Position;
+    /// The position `steps` places clockwise from this one
+    fn rotate(self, steps: usize) -> Position;
+    /// Standard dealer of a 1-based board number: North on board 1, then
+    /// clockwise, repeating every four boards
+    fn dealer_for_board(board_number: usize) -> Position;
+}
+
+impl PositionExt for Position {
+    fn next(self) -> Position {
+        self.rotate(1)
+    }
+
+    fn prev(self) -> Position {
+        self.rotate(3)
+    }
+
+    fn rotate(self, steps: usize) -> Position {
+        match (self as usize + steps) % 4 {
+            0 => Position::North,
+            1 => Position::East,
+            2 => Position::South,
+            _ => Position::West,
+        }
+    }
+
+    fn dealer_for_board(board_number: usize) -> Position {
+        Position::North.rotate((board_number + 3) % 4)
+    }
+}
+
+/// A partnership: North-South or East-West
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    NS,
+    EW,
+}
+
+impl Side {
+    /// The two seats of this side (North or East first)
+    pub fn positions(self) -> [Position; 2] {
+        match self {
+            Side::NS => [Position::North, Position::South],
+            Side::EW => [Position::East, Position::West],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_and_prev() {
+        assert_eq!(Position::North.next(), Position::East);
+        assert_eq!(Position::West.next(), Position::North);
+        assert_eq!(Position::North.prev(), Position::West);
+        assert_eq!(Position::North.next().next().next().next(), Position::North);
+        for pos in Position::ALL {
+            assert_eq!(pos.next().prev(), pos);
+            assert_eq!(pos.rotate(2), pos.partner());
+        }
+    }
+
+    #[test]
+    fn test_dealer_for_board() {
+        assert_eq!(Position::dealer_for_board(1), Position::North);
+        assert_eq!(Position::dealer_for_board(2), Position::East);
+        assert_eq!(Position::dealer_for_board(8), Position::West);
+        assert_eq!(Position::dealer_for_board(16), Position::West);
+        assert_eq!(Position::dealer_for_board(17), Position::North);
+    }
+
+    #[test]
+    fn test_side_positions() {
+        assert_eq!(Side::NS.positions(), [Position::North, Position::South]);
+        assert_eq!(Side::EW.positions(), [Position::East, Position::West]);
+    }
+
+    #[test]
+    fn test_partner() {
+        assert_eq!(Position::North.partner(), Position::South);
+        assert_eq!(Position::East.partner(), Position::West);
+    }
+}
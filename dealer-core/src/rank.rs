@@ -0,0 +1,68 @@
+//! Rank parsing helper
+//!
+//! `Rank` is bridge-types' type, so parsing from a character is provided
+//! through an extension trait, the same way `SuitExt` extends `Suit`.
+
+use crate::Rank;
+
+/// Character conversion for ranks
+pub trait RankExt: Sized {
+    /// Parse a rank character: `A K Q J T 9`..`2`, case-insensitive
+    fn from_char(c: char) -> Option<Self>;
+}
+
+impl RankExt for Rank {
+    fn from_char(c: char) -> Option<Rank> {
+        match c.to_ascii_uppercase() {
+            'A' => Some(Rank::Ace),
+            'K' => Some(Rank::King),
+            'Q' => Some(Rank::Queen),
+            'J' => Some(Rank::Jack),
+            'T' => Some(Rank::Ten),
+            '9' => Some(Rank::Nine),
+            '8' => Some(Rank::Eight),
+            '7' => Some(Rank::Seven),
+            '6' => Some(Rank::Six),
+            '5' => Some(Rank::Five),
+            '4' => Some(Rank::Four),
+            '3' => Some(Rank::Three),
+            '2' => Some(Rank::Two),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_char_every_rank() {
+        let ranks = [
+            Rank::Two,
+            Rank::Three,
+            Rank::Four,
+            Rank::Five,
+            Rank::Six,
+            Rank::Seven,
+            Rank::Eight,
+            Rank::Nine,
+            Rank::Ten,
+            Rank::Jack,
+            Rank::Queen,
+            Rank::King,
+            Rank::Ace,
+        ];
+        for (c, rank) in "23456789TJQKA".chars().zip(ranks) {
+            assert_eq!(Rank::from_char(c), Some(rank));
+            assert_eq!(Rank::from_char(c.to_ascii_lowercase()), Some(rank));
+        }
+    }
+
+    #[test]
+    fn test_from_char_invalid() {
+        for c in ['1', '0', 'X', 'x', 'S', ' ', '-', 'Ä'] {
+            assert_eq!(Rank::from_char(c), None, "{:?}", c);
+        }
+    }
+}
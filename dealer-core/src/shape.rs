@@ -190,6 +190,70 @@ impl ShapeMask {
         mask
     }
 
+    /// Create a mask for a range shape: (min, max) length per suit, None = unbounded.
+    /// E.g., [(Some(5), None), (Some(4), None), (None, None), (None, None)] matches 5+ 4+ x x.
+    pub fn range(pattern: [(Option<u8>, Option<u8>); 4]) -> Self {
+        let mut mask = ShapeMask::empty();
+        let in_range = |len: usize, (min, max): (Option<u8>, Option<u8>)| {
+            min.is_none_or(|m| len >= m as usize) && max.is_none_or(|m| len <= m as usize)
+        };
+
+        for s in 0..14usize {
+            for h in 0..(14 - s) {
+                for d in 0..(14 - s - h) {
+                    let c = 13 - s - h - d;
+                    if in_range(s, pattern[0])
+                        && in_range(h, pattern[1])
+                        && in_range(d, pattern[2])
+                        && in_range(c, pattern[3])
+                    {
+                        mask.set(shape_to_index(s, h, d, c));
+                    }
+                }
+            }
+        }
+        mask
+    }
+
+    /// Create a mask for a range shape plus "some other suit" minimums.
+    /// Each entry of `other_mins` needs its own suit among those left unbounded
+    /// (`(None, None)`) in `pattern`. E.g., a 5+ spade range with `other_mins` of
+    /// `[4]` matches 5+ spades and at least one other 4+ suit.
+    pub fn constraint(pattern: [(Option<u8>, Option<u8>); 4], other_mins: &[u8]) -> Self {
+        let mut mins = other_mins.to_vec();
+        mins.sort_unstable_by(|a, b| b.cmp(a));
+        let range = ShapeMask::range(pattern);
+
+        let mut mask = ShapeMask::empty();
+        for s in 0..14usize {
+            for h in 0..(14 - s) {
+                for d in 0..(14 - s - h) {
+                    let c = 13 - s - h - d;
+                    let index = shape_to_index(s, h, d, c);
+                    if !range.contains(index) {
+                        continue;
+                    }
+                    let lengths = [s, h, d, c];
+                    let mut free: Vec<usize> = (0..4)
+                        .filter(|&suit| pattern[suit] == (None, None))
+                        .map(|suit| lengths[suit])
+                        .collect();
+                    free.sort_unstable_by(|a, b| b.cmp(a));
+                    // Longest free suits against the largest minimums, pairwise
+                    if mins.len() <= free.len()
+                        && mins
+                            .iter()
+                            .zip(&free)
+                            .all(|(&min, &len)| len >= min as usize)
+                    {
+                        mask.set(index);
+                    }
+                }
+            }
+        }
+        mask
+    }
+
     /// Create a mask for an "any" distribution (order doesn't matter).
     /// E.g., any_distribution([4,3,3,3]) matches 4-3-3-3 in any suit order.
     pub fn any_distribution(pattern: [u8; 4]) -> Self {
@@ -350,6 +414,63 @@ mod tests {
         assert_eq!(mask.count(), 5);
     }
 
+    #[test]
+    fn test_range_shape() {
+        // 5+ 4+ x x
+        let mask = ShapeMask::range([(Some(5), None), (Some(4), None), (None, None), (None, None)]);
+        assert!(mask.matches(5, 4, 3, 1));
+        assert!(mask.matches(6, 5, 1, 1));
+        assert!(mask.matches(5, 8, 0, 0));
+        assert!(!mask.matches(4, 4, 3, 2));
+        assert!(!mask.matches(5, 3, 3, 2));
+
+        // 2-4 in every suit: 4-3-3-3 and 4-4-3-2 patterns, but no 5-card suit or singleton
+        let mask = ShapeMask::range([(Some(2), Some(4)); 4]);
+        assert!(mask.matches(4, 3, 3, 3));
+        assert!(mask.matches(2, 4, 4, 3));
+        assert!(!mask.matches(5, 3, 3, 2));
+        assert!(!mask.matches(4, 4, 4, 1));
+        // 4 permutations of 4333 + 12 of 4432
+        assert_eq!(mask.count(), 16);
+    }
+
+    #[test]
+    fn test_constraint_shape() {
+        // 5+ spades and at least one other 4+ suit
+        let five_spades = [(Some(5), None), (None, None), (None, None), (None, None)];
+        let mask = ShapeMask::constraint(five_spades, &[4]);
+        assert!(mask.matches(5, 4, 2, 2));
+        assert!(mask.matches(5, 2, 2, 4));
+        assert!(mask.matches(6, 0, 3, 4));
+        assert!(!mask.matches(5, 3, 3, 2));
+        assert!(!mask.matches(7, 3, 2, 1));
+        assert!(!mask.matches(4, 4, 4, 1));
+
+        // Two other suits each need their own four cards
+        let mask = ShapeMask::constraint(five_spades, &[4, 4]);
+        assert!(mask.matches(5, 4, 4, 0));
+        assert!(!mask.matches(5, 4, 3, 1));
+
+        // A bounded suit never counts as the other suit
+        let mask = ShapeMask::constraint(
+            [
+                (Some(4), None),
+                (Some(4), Some(4)),
+                (None, None),
+                (None, None),
+            ],
+            &[4],
+        );
+        assert!(mask.matches(4, 4, 4, 1));
+        assert!(!mask.matches(5, 4, 2, 2));
+
+        // No minimums is the plain range
+        assert_eq!(
+            ShapeMask::constraint(five_spades, &[]),
+            ShapeMask::range(five_spades)
+        );
+    }
+
     #[test]
     fn test_any_distribution() {
         // any 4333
@@ -0,0 +1,67 @@
+//! Suit ordering helpers
+//!
+//! `Suit` is bridge-types' type: it already derives `Ord` with clubs lowest and
+//! spades highest, and its `Suit::ALL` runs clubs to spades. Display and shape
+//! code wants the opposite order, so that is provided through an extension
+//! trait rather than yet another ad-hoc array. Parsing a suit letter lives
+//! here too.
+
+use crate::Suit;
+
+/// Bridge display order for suits
+pub trait SuitExt: Sized {
+    /// Spades, hearts, diamonds, clubs: highest rank first
+    const DESCENDING: [Suit; 4];
+
+    /// Parse a suit letter `S H D C`, case-insensitive
+    fn from_char(c: char) -> Option<Self>;
+}
+
+impl SuitExt for Suit {
+    const DESCENDING: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+
+    fn from_char(c: char) -> Option<Suit> {
+        match c.to_ascii_uppercase() {
+            'S' => Some(Suit::Spades),
+            'H' => Some(Suit::Hearts),
+            'D' => Some(Suit::Diamonds),
+            'C' => Some(Suit::Clubs),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_descending_is_rank_order() {
+        assert!(Suit::DESCENDING.windows(2).all(|pair| pair[0] > pair[1]));
+
+        let mut reversed = Suit::ALL;
+        reversed.reverse();
+        assert_eq!(reversed, Suit::DESCENDING);
+    }
+
+    #[test]
+    fn test_sorting_suits() {
+        let mut suits = vec![Suit::Hearts, Suit::Clubs, Suit::Spades, Suit::Diamonds];
+        suits.sort();
+        assert_eq!(suits, Suit::ALL);
+
+        suits.sort_by(|a, b| b.cmp(a));
+        assert_eq!(suits, Suit::DESCENDING);
+    }
+
+    #[test]
+    fn test_from_char() {
+        for (c, suit) in "SHDC".chars().zip(Suit::DESCENDING) {
+            assert_eq!(Suit::from_char(c), Some(suit));
+            assert_eq!(Suit::from_char(c.to_ascii_lowercase()), Some(suit));
+        }
+        for c in ['N', 'A', 'T', '1', ' ', '♠'] {
+            assert_eq!(Suit::from_char(c), None, "{:?}", c);
+        }
+    }
+}
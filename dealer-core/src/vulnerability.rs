@@ -0,0 +1,67 @@
+//! Vulnerability of the two partnerships
+//!
+//! Shared by the PBN formatters (the `[Vulnerable]` tag and board rotation)
+//! and by dealer-dds scoring (par and contract scores).
+
+use crate::Position;
+
+/// Vulnerability of the two partnerships
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Vulnerability {
+    None,
+    NS,
+    EW,
+    All,
+}
+
+impl Vulnerability {
+    /// Returns true if the given declarer's side is vulnerable
+    pub fn is_vulnerable(&self, declarer: Position) -> bool {
+        let ns = matches!(declarer, Position::North | Position::South);
+        match self {
+            Vulnerability::None => false,
+            Vulnerability::NS => ns,
+            Vulnerability::EW => !ns,
+            Vulnerability::All => true,
+        }
+    }
+
+    /// Standard vulnerability of a 1-based board number, repeating every 16 boards
+    ///
+    /// Boards 1-16 run None, NS, EW, All, NS, EW, All, None, EW, All, None,
+    /// NS, All, None, NS, EW.
+    pub fn for_board(board_number: usize) -> Vulnerability {
+        const ROTATION: [Vulnerability; 16] = {
+            use Vulnerability::*;
+            [
+                None, NS, EW, All, NS, EW, All, None, EW, All, None, NS, All, None, NS, EW,
+            ]
+        };
+        ROTATION[(board_number + 15) % 16]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_vulnerable() {
+        assert!(!Vulnerability::None.is_vulnerable(Position::North));
+        assert!(Vulnerability::NS.is_vulnerable(Position::South));
+        assert!(!Vulnerability::NS.is_vulnerable(Position::East));
+        assert!(Vulnerability::EW.is_vulnerable(Position::West));
+        assert!(!Vulnerability::EW.is_vulnerable(Position::North));
+        assert!(Vulnerability::All.is_vulnerable(Position::East));
+    }
+
+    #[test]
+    fn test_vulnerability_for_board() {
+        assert_eq!(Vulnerability::for_board(1), Vulnerability::None);
+        assert_eq!(Vulnerability::for_board(2), Vulnerability::NS);
+        assert_eq!(Vulnerability::for_board(8), Vulnerability::None);
+        assert_eq!(Vulnerability::for_board(13), Vulnerability::All);
+        assert_eq!(Vulnerability::for_board(16), Vulnerability::EW);
+        assert_eq!(Vulnerability::for_board(17), Vulnerability::None);
+    }
+}
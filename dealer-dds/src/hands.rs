@@ -0,0 +1,129 @@
+//! bridge-solver hands built from dealer-core deals
+//!
+//! `hands_from_deal()` loads a `Deal` into the engine card by card, without
+//! formatting and parsing a PBN string. bridge-solver's `Hands` has no public
+//! HCP count, so `HandsExt` adds one, read straight from the `Cards` bitsets,
+//! for checking the result against the deal. Built with the `solver2` feature.
+
+use crate::solver2::{self, Hands, Seat};
+use dealer_core::{Deal, Position, Suit};
+
+/// High card point count for bridge-solver `Hands`
+pub trait HandsExt {
+    /// High card points held by `seat`: A=4, K=3, Q=2, J=1
+    ///
+    /// A full deal totals 40 over the four seats.
+    fn hcp(&self, seat: Seat) -> u8;
+}
+
+impl HandsExt for Hands {
+    fn hcp(&self, seat: Seat) -> u8 {
+        self[seat]
+            .iter()
+            .map(|card| match solver2::rank_of(card) {
+                solver2::ACE => 4,
+                solver2::KING => 3,
+                solver2::QUEEN => 2,
+                solver2::JACK => 1,
+                _ => 0,
+            })
+            .sum()
+    }
+}
+
+/// bridge-solver `Hands` holding the same cards as a deal
+pub fn hands_from_deal(deal: &Deal) -> Hands {
+    let mut hands = Hands::default();
+    for position in Position::ALL {
+        let seat = solver_seat(position);
+        for card in deal.hand(position).cards() {
+            let suit = match card.suit {
+                Suit::Spades => solver2::SPADE,
+                Suit::Hearts => solver2::HEART,
+                Suit::Diamonds => solver2::DIAMOND,
+                Suit::Clubs => solver2::CLUB,
+            };
+            hands[seat].add(solver2::card_of(suit, solver2::TWO + card.rank as usize));
+        }
+    }
+    hands
+}
+
+/// bridge-solver seat for a dealer-core position
+pub fn solver_seat(position: Position) -> Seat {
+    match position {
+        Position::North => solver2::NORTH,
+        Position::East => solver2::EAST,
+        Position::South => solver2::SOUTH,
+        Position::West => solver2::WEST,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dealer_core::DealGenerator;
+
+    fn total_hcp(hands: &Hands) -> u8 {
+        Position::ALL
+            .iter()
+            .map(|&position| hands.hcp(solver_seat(position)))
+            .sum()
+    }
+
+    #[test]
+    fn test_hcp_of_fixed_deal() {
+        // North 10, East 11, South 17, West 2
+        let hands = Hands::from_pbn(
+            "N:AK72.Q93.J84.T52 QJ9.K85.A6.J9874 T84.AJ7.KQT93.AK 653.T642.752.Q63",
+        )
+        .expect("valid PBN");
+
+        assert_eq!(hands.hcp(solver_seat(Position::North)), 10);
+        assert_eq!(hands.hcp(solver_seat(Position::East)), 11);
+        assert_eq!(hands.hcp(solver_seat(Position::South)), 17);
+        assert_eq!(hands.hcp(solver_seat(Position::West)), 2);
+        assert_eq!(total_hcp(&hands), 40);
+    }
+
+    #[test]
+    fn test_hands_from_deal_matches_pbn() {
+        for seed in 1..=20 {
+            let deal = DealGenerator::new(seed).generate();
+            let hands = hands_from_deal(&deal);
+            let parsed = Hands::from_pbn(&deal.to_pbn_string(Position::North))
+                .expect("dealer-core PBN parses as solver Hands");
+
+            for position in Position::ALL {
+                let seat = solver_seat(position);
+                assert!(
+                    hands[seat].iter().eq(parsed[seat].iter()),
+                    "seed {} {:?}",
+                    seed,
+                    position
+                );
+                assert_eq!(hands.hcp(seat), deal.hand(position).hcp());
+            }
+        }
+    }
+
+    #[test]
+    fn test_hcp_matches_deal() {
+        for seed in 1..=20 {
+            let deal = DealGenerator::new(seed).generate();
+            let hands = Hands::from_pbn(&deal.to_pbn_string(Position::North))
+                .expect("dealer-core PBN parses as solver Hands");
+
+            assert_eq!(total_hcp(&hands), 40, "seed {}", seed);
+            for position in Position::ALL {
+                assert_eq!(
+                    hands.hcp(solver_seat(position)),
+                    deal.hand(position).hcp(),
+                    "seed {} {:?}",
+                    seed,
+                    position
+                );
+            }
+        }
+    }
+}
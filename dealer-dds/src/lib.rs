@@ -4,21 +4,62 @@
 //! the number of tricks that can be made by each side in each denomination
 //! when all four hands are visible.
 
-use dealer_core::{Card, Deal, Position, Suit};
+use dealer_core::{Card, Deal, Position, PositionExt, Suit};
+
+#[cfg(feature = "solver2")]
+mod hands;
+mod pbn;
+mod score;
+
+#[cfg(feature = "solver2")]
+pub use hands::{hands_from_deal, solver_seat, HandsExt};
+pub use pbn::{format_dd_table, format_makeable_contracts, format_printpbn_with_dds};
+pub use score::{contract_score, dealer_imps, imp_diff, matchpoints, ParContract, ParResult};
 
 /// New solver implementation (port of macroxue/bridge-solver)
-/// Re-exported from bridge-solver crate
+/// Re-exported from bridge-solver crate; enable with the `solver2` feature
+#[cfg(feature = "solver2")]
 pub use bridge_solver as solver2;
+
+// Vulnerability lives in dealer-core; re-exported for existing callers
+pub use dealer_core::Vulnerability;
+use std::cmp::Reverse;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Helper function to get the next position in clockwise order
 fn next_position(pos: Position) -> Position {
-    match pos {
-        Position::North => Position::East,
-        Position::East => Position::South,
-        Position::South => Position::West,
-        Position::West => Position::North,
-    }
+    pos.next()
+}
+
+/// Owners of a suit's top cards, from the ace down, while they all belong to `side`
+fn top_run(deal: &Deal, suit: Suit, side: &[Position]) -> Vec<Position> {
+    let mut cards: Vec<(Card, Position)> = Position::ALL
+        .iter()
+        .flat_map(|&position| {
+            deal.hand(position)
+                .cards_in_suit(suit)
+                .into_iter()
+                .map(move |card| (card, position))
+        })
+        .collect();
+    cards.sort_by_key(|(card, _)| Reverse(card.rank));
+    cards
+        .into_iter()
+        .map(|(_, position)| position)
+        .take_while(|position| side.contains(position))
+        .collect()
+}
+
+/// Top trumps a side is sure to win: those held in its better hand within the
+/// side's unbroken run from the ace. Every trick one of them is played to goes
+/// to the side, and one hand never plays two cards to the same trick.
+fn sure_trump_tricks(deal: &Deal, trump: Suit, side: [Position; 2]) -> u8 {
+    let run = top_run(deal, trump, &side);
+    side.iter()
+        .map(|&hand| run.iter().filter(|&&owner| owner == hand).count() as u8)
+        .max()
+        .unwrap_or(0)
 }
 
 /// Denomination for double-dummy analysis
@@ -72,6 +113,35 @@ impl Denomination {
             Denomination::NoTrump => 'N',
         }
     }
+
+    /// Parse the character from to_char(), case-insensitively
+    pub fn from_char(c: char) -> Option<Self> {
+        match c.to_ascii_uppercase() {
+            'C' => Some(Denomination::Clubs),
+            'D' => Some(Denomination::Diamonds),
+            'H' => Some(Denomination::Hearts),
+            'S' => Some(Denomination::Spades),
+            'N' => Some(Denomination::NoTrump),
+            _ => None,
+        }
+    }
+}
+
+impl std::str::FromStr for Denomination {
+    type Err = String;
+
+    /// Parse "C", "D", "H", "S", "N" or "NT", case-insensitively
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let parsed = match (chars.next(), chars.as_str()) {
+            (Some(c), "") => Denomination::from_char(c),
+            (Some(c), rest) if rest.eq_ignore_ascii_case("t") => {
+                Denomination::from_char(c).filter(|d| *d == Denomination::NoTrump)
+            }
+            _ => None,
+        };
+        parsed.ok_or_else(|| format!("Invalid denomination '{}', expected C, D, H, S or NT", s))
+    }
 }
 
 /// Result of double-dummy analysis for a single denomination and declarer
@@ -112,6 +182,34 @@ impl DoubleDummyResult {
         self.tricks[denom_idx][decl_idx]
     }
 
+    /// Most tricks North-South can take in a denomination, whichever of them declares
+    pub fn ns_tricks(&self, denomination: Denomination) -> u8 {
+        self.get_tricks(denomination, Position::North)
+            .max(self.get_tricks(denomination, Position::South))
+    }
+
+    /// Most tricks East-West can take in a denomination, whichever of them declares
+    pub fn ew_tricks(&self, denomination: Denomination) -> u8 {
+        self.get_tricks(denomination, Position::East)
+            .max(self.get_tricks(denomination, Position::West))
+    }
+
+    /// The denomination in which North-South take the most tricks, and that count
+    ///
+    /// Ties go to the higher-ranking denomination (NT, then spades, down to clubs).
+    pub fn best_ns_contract(&self) -> (Denomination, u8) {
+        Denomination::ALL
+            .iter()
+            .map(|&denomination| (denomination, self.ns_tricks(denomination)))
+            .fold((Denomination::Clubs, 0), |best, candidate| {
+                if candidate.1 >= best.1 {
+                    candidate
+                } else {
+                    best
+                }
+            })
+    }
+
     /// Get all results as a vector of TrickResult
     pub fn all_results(&self) -> Vec<TrickResult> {
         let mut results = Vec::new();
@@ -126,14 +224,132 @@ impl DoubleDummyResult {
         }
         results
     }
+
+    /// All 20 results, most tricks first
+    ///
+    /// Ties are ordered by denomination, highest first (NT, spades, ... clubs),
+    /// then by declarer in North, East, South, West order.
+    pub fn ranked(&self) -> Vec<TrickResult> {
+        let mut results = self.all_results();
+        results.sort_by_key(|r| {
+            (
+                Reverse(r.tricks),
+                Reverse(r.denomination as usize),
+                r.declarer as usize,
+            )
+        });
+        results
+    }
+
+    /// Contracts that make at `level` or higher (at least `6 + level` tricks), in `ranked()` order
+    pub fn makeable(&self, level: u8) -> Vec<TrickResult> {
+        self.ranked()
+            .into_iter()
+            .filter(|result| result.tricks >= 6 + level)
+            .collect()
+    }
+
+    /// Encode all 20 results as hex digits (0-9, a-d for 0-13 tricks)
+    ///
+    /// Declarers run North, South, East, West, each in NT, S, H, D, C order: the
+    /// same layout as PBN's `[DoubleDummyTricks]` tag.
+    pub fn to_compact(&self) -> String {
+        COMPACT_DECLARERS
+            .iter()
+            .flat_map(|&declarer| {
+                COMPACT_DENOMINATIONS.iter().map(move |&denomination| {
+                    let tricks = self.get_tricks(denomination, declarer);
+                    char::from_digit(tricks as u32, 16).unwrap_or('0')
+                })
+            })
+            .collect()
+    }
+
+    /// Parse the to_compact() encoding (hex digits in either case)
+    pub fn from_compact(s: &str) -> Result<Self, String> {
+        let digits: Vec<char> = s.chars().collect();
+        if digits.len() != 20 {
+            return Err(format!(
+                "Expected 20 trick digits, got {} in '{}'",
+                digits.len(),
+                s
+            ));
+        }
+
+        let mut result = Self::new();
+        let cells = COMPACT_DECLARERS.iter().flat_map(|&declarer| {
+            COMPACT_DENOMINATIONS
+                .iter()
+                .map(move |&denomination| (declarer, denomination))
+        });
+        for ((declarer, denomination), c) in cells.zip(digits) {
+            let tricks = c
+                .to_digit(16)
+                .filter(|&t| t <= 13)
+                .ok_or_else(|| format!("Invalid trick digit '{}' in '{}'", c, s))?;
+            result.set_tricks(denomination, declarer, tricks as u8);
+        }
+        Ok(result)
+    }
+
+    /// Compute the par score and par contract(s), assuming perfect bidding
+    ///
+    /// The score is from the North-South point of view. Sacrifices that go down
+    /// are scored doubled.
+    pub fn par(&self, vulnerability: Vulnerability) -> ParResult {
+        score::par(self, vulnerability)
+    }
 }
 
+/// Declarer order of the compact encoding (as in PBN's DoubleDummyTricks)
+const COMPACT_DECLARERS: [Position; 4] = [
+    Position::North,
+    Position::South,
+    Position::East,
+    Position::West,
+];
+
+/// Denomination order of the compact encoding (as in PBN's DoubleDummyTricks)
+const COMPACT_DENOMINATIONS: [Denomination; 5] = [
+    Denomination::NoTrump,
+    Denomination::Spades,
+    Denomination::Hearts,
+    Denomination::Diamonds,
+    Denomination::Clubs,
+];
+
 impl Default for DoubleDummyResult {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Why a card cannot be played in the current game state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayError {
+    /// The card was played by someone other than the player on turn (who is given)
+    OutOfTurn(Position),
+    /// The player on turn does not hold the card
+    NotInHand,
+    /// The player holds a card of the suit led but played another suit
+    MustFollowSuit,
+    /// All tricks have already been played
+    GameOver,
+}
+
+impl std::fmt::Display for PlayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PlayError::OutOfTurn(player) => write!(f, "{} is on turn", player.to_char()),
+            PlayError::NotInHand => write!(f, "the player on turn does not hold it"),
+            PlayError::MustFollowSuit => write!(f, "the player on turn must follow suit"),
+            PlayError::GameOver => write!(f, "all tricks have been played"),
+        }
+    }
+}
+
+impl std::error::Error for PlayError {}
+
 /// Game state for a single trick in progress
 #[derive(Clone, Debug)]
 struct TrickState {
@@ -206,8 +422,11 @@ impl TrickState {
 }
 
 /// Complete game state for double-dummy solving
+///
+/// Build one part way through the play with `GameState::from_played()` and
+/// pass it to `DoubleDummySolver::analyze_position()`.
 #[derive(Clone)]
-struct GameState {
+pub struct GameState {
     /// Cards remaining in each hand (by position)
     hands: [Vec<Card>; 4],
     /// Current trick in progress
@@ -249,8 +468,29 @@ impl GameState {
         }
     }
 
-    /// Get the next player to act
-    fn next_player(&self) -> Position {
+    /// The position after `played` cards, in order from the opening lead
+    ///
+    /// The opening lead comes from declarer's left. Fails at the first play
+    /// made out of turn, with a card not in that player's hand, that
+    /// revokes, or that comes after the last trick.
+    pub fn from_played(
+        deal: &Deal,
+        declarer: Position,
+        denomination: Denomination,
+        played: &[(Position, Card)],
+    ) -> Result<Self, PlayError> {
+        let mut state = Self::new(deal, declarer, denomination.to_suit());
+        for &(player, card) in played {
+            if !state.is_terminal() && player != state.next_player() {
+                return Err(PlayError::OutOfTurn(state.next_player()));
+            }
+            state.play_card(card)?;
+        }
+        Ok(state)
+    }
+
+    /// The player to play the next card
+    pub fn next_player(&self) -> Position {
         let cards_played = self.current_trick.cards_played.len();
         if cards_played == 0 {
             self.current_trick.leader
@@ -287,17 +527,68 @@ impl GameState {
         hand.clone()
     }
 
-    /// Play a card and update state
-    fn play_card(&mut self, card: Card) -> bool {
+    /// Legal moves in search order: highest rank first for the side trying to
+    /// win tricks, lowest first for the side trying to hold them down
+    ///
+    /// Only one card of each run of equivalent cards is kept. Two cards of a
+    /// suit in the same hand play alike when no other hand and no card on
+    /// the table holds a rank between them, so searching both is wasted work.
+    fn ordered_moves(&self, maximizing: bool) -> Vec<Card> {
+        let mut moves = self.legal_moves();
+        if maximizing {
+            moves.sort_by_key(|card| Reverse(card.rank));
+        } else {
+            moves.sort_by_key(|card| card.rank);
+        }
+
+        let player = self.next_player();
+        let others: Vec<Card> = Position::ALL
+            .into_iter()
+            .filter(|&position| position != player)
+            .flat_map(|position| self.hands[position as usize].iter().copied())
+            .chain(
+                self.current_trick
+                    .cards_played
+                    .iter()
+                    .map(|&(_, card)| card),
+            )
+            .collect();
+        let mut kept: Vec<Card> = Vec::with_capacity(moves.len());
+        for card in moves {
+            let equivalent = kept.iter().any(|&k| {
+                k.suit == card.suit
+                    && !others.iter().any(|&o| {
+                        o.suit == card.suit
+                            && o.rank > k.rank.min(card.rank)
+                            && o.rank < k.rank.max(card.rank)
+                    })
+            });
+            if !equivalent {
+                kept.push(card);
+            }
+        }
+        kept
+    }
+
+    /// Play a card for the player on turn and update state
+    fn play_card(&mut self, card: Card) -> Result<(), PlayError> {
+        if self.is_terminal() {
+            return Err(PlayError::GameOver);
+        }
+
         let player = self.next_player();
         let hand = &mut self.hands[player as usize];
 
-        // Remove card from hand
-        if let Some(pos) = hand.iter().position(|&c| c == card) {
-            hand.remove(pos);
-        } else {
-            return false; // Invalid move
+        let pos = hand
+            .iter()
+            .position(|&c| c == card)
+            .ok_or(PlayError::NotInHand)?;
+        if let Some(suit_led) = self.current_trick.suit_led() {
+            if card.suit != suit_led && hand.iter().any(|c| c.suit == suit_led) {
+                return Err(PlayError::MustFollowSuit);
+            }
         }
+        hand.remove(pos);
 
         // Record play: high 2 bits = position, low 6 bits = card index
         let encoded = ((player as u8) << 6) | card.to_index();
@@ -321,7 +612,12 @@ impl GameState {
             self.current_trick = TrickState::new(winner, self.current_trick.trump);
         }
 
-        true
+        Ok(())
+    }
+
+    /// Number of completed tricks
+    pub fn tricks_played(&self) -> u8 {
+        self.tricks_played
     }
 
     /// Check if game is over
@@ -355,22 +651,20 @@ impl GameState {
 
     /// Hash the game state for TT lookup
     /// Only valid at trick boundaries (no cards in current trick)
+    ///
+    /// Cards never change hands, so within one deal the set of cards still
+    /// held fixes all four hands. That 52-bit set and the leader pack into
+    /// the key exactly, so no two positions share a key. Tricks already won
+    /// are left out: the table stores the tricks still to come, which are
+    /// the same however the position was reached.
     fn hash(&self) -> u64 {
-        let mut hash = 0u64;
-
-        // Hash each hand's cards using bit rotation
-        for (pos_idx, hand) in self.hands.iter().enumerate() {
-            for card in hand {
-                let card_bit = 1u64 << (card.to_index() as u64 % 52);
-                hash ^= card_bit.rotate_left((pos_idx * 13) as u32);
-            }
-        }
+        let remaining = self
+            .hands
+            .iter()
+            .flatten()
+            .fold(0u64, |mask, card| mask | (1u64 << card.to_index()));
 
-        // Include leader and tricks won for uniqueness
-        hash ^= (self.current_trick.leader as u64) << 56;
-        hash ^= (self.declarer_tricks as u64) << 52;
-
-        hash
+        remaining | ((self.current_trick.leader as u64) << 52)
     }
 }
 
@@ -385,8 +679,12 @@ enum TTEntry {
     UpperBound(u8),
 }
 
-/// Transposition table for caching results
-type TranspositionTable = HashMap<u64, TTEntry>;
+/// Transposition table for caching results, plus the number of nodes searched
+#[derive(Default)]
+struct TranspositionTable {
+    entries: HashMap<u64, TTEntry>,
+    nodes: u64,
+}
 
 /// Result with play sequence for debugging
 #[derive(Debug, Clone)]
@@ -398,23 +696,42 @@ pub struct SolveResultWithLine {
 }
 
 /// Solver for double-dummy analysis
+///
+/// Each solve starts from an empty transposition table, so no state carries
+/// over between solves.
 pub struct DoubleDummySolver {
     deal: Deal,
+    /// Nodes searched by the most recent solve (atomic so solves can run in parallel)
+    last_node_count: AtomicU64,
 }
 
 impl DoubleDummySolver {
     /// Create a new solver for the given deal
     pub fn new(deal: Deal) -> Self {
-        Self { deal }
+        Self {
+            deal,
+            last_node_count: AtomicU64::new(0),
+        }
+    }
+
+    /// Number of alpha-beta nodes visited by the most recent solve
+    ///
+    /// With the `solver2` feature this is bridge-solver's own node count.
+    ///
+    /// Useful for profiling which contracts are expensive. After
+    /// `solve_all_parallel()` it reflects whichever solve finished last.
+    pub fn last_node_count(&self) -> u64 {
+        self.last_node_count.load(Ordering::Relaxed)
     }
 
     /// Solve for all denominations and all declarers
     pub fn solve_all(&self) -> DoubleDummyResult {
+        let table = self.solve_table();
         let mut result = DoubleDummyResult::new();
 
         for denomination in Denomination::ALL {
             for declarer in Position::ALL {
-                let tricks = self.solve(denomination, declarer);
+                let tricks = table[denomination as usize][declarer as usize];
                 result.set_tricks(denomination, declarer, tricks);
             }
         }
@@ -422,13 +739,108 @@ impl DoubleDummySolver {
         result
     }
 
+    /// Solve all 20 contracts in one run, indexed `[denomination][declarer]`
+    ///
+    /// One transposition table serves every contract: it is cleared between
+    /// contracts, since its entries only hold for one trump suit and declarer,
+    /// but keeps its allocation, so later contracts do not regrow it. With the
+    /// `solver2` feature each contract goes to the bridge-solver engine instead.
+    pub fn solve_table(&self) -> [[u8; 4]; 5] {
+        #[cfg(not(feature = "solver2"))]
+        let mut tt = TranspositionTable::default();
+        let mut table = [[0; 4]; 5];
+        for denomination in Denomination::ALL {
+            for declarer in Position::ALL {
+                #[cfg(feature = "solver2")]
+                let tricks = self.solve_engine(denomination, declarer);
+                #[cfg(not(feature = "solver2"))]
+                let tricks = self.solve_in(denomination, declarer, &mut tt);
+                table[denomination as usize][declarer as usize] = tricks;
+            }
+        }
+        table
+    }
+
+    /// Solve for all denominations and all declarers in parallel
+    ///
+    /// Same result as solve_all(), with the 20 independent solves distributed
+    /// across threads using rayon.
+    #[cfg(feature = "parallel")]
+    pub fn solve_all_parallel(&self) -> DoubleDummyResult {
+        use rayon::prelude::*;
+
+        let combinations: Vec<(Denomination, Position)> = Denomination::ALL
+            .iter()
+            .flat_map(|&denomination| Position::ALL.iter().map(move |&pos| (denomination, pos)))
+            .collect();
+
+        let solved: Vec<(Denomination, Position, u8)> = combinations
+            .into_par_iter()
+            .map(|(denomination, declarer)| {
+                (denomination, declarer, self.solve(denomination, declarer))
+            })
+            .collect();
+
+        let mut result = DoubleDummyResult::new();
+        for (denomination, declarer, tricks) in solved {
+            result.set_tricks(denomination, declarer, tricks);
+        }
+        result
+    }
+
     /// Solve for a specific denomination and declarer
+    ///
+    /// Built with the `solver2` feature, the bridge-solver engine does the
+    /// search; otherwise the built-in alpha-beta search does.
     pub fn solve(&self, denomination: Denomination, declarer: Position) -> u8 {
+        #[cfg(feature = "solver2")]
+        let tricks = self.solve_engine(denomination, declarer);
+        #[cfg(not(feature = "solver2"))]
+        let tricks = self.solve_in(denomination, declarer, &mut TranspositionTable::default());
+        tricks
+    }
+
+    /// Solve one contract with the bridge-solver engine
+    ///
+    /// The engine counts North-South tricks with the player on declarer's
+    /// left on lead; East-West declarers take the rest.
+    #[cfg(feature = "solver2")]
+    fn solve_engine(&self, denomination: Denomination, declarer: Position) -> u8 {
+        let trump = match denomination {
+            Denomination::Spades => solver2::SPADE,
+            Denomination::Hearts => solver2::HEART,
+            Denomination::Diamonds => solver2::DIAMOND,
+            Denomination::Clubs => solver2::CLUB,
+            Denomination::NoTrump => solver2::NOTRUMP,
+        };
+        let leader = solver_seat(declarer.next());
+        let mut solver = solver2::Solver::new(hands_from_deal(&self.deal), trump, leader);
+        let ns_tricks = solver.solve();
+        self.last_node_count
+            .store(solver2::get_node_count(), Ordering::Relaxed);
+
+        match declarer {
+            Position::North | Position::South => ns_tricks,
+            Position::East | Position::West => self.deal.hand(declarer).len() as u8 - ns_tricks,
+        }
+    }
+
+    /// Solve one contract using `tt`, which is emptied first
+    #[cfg_attr(feature = "solver2", allow(dead_code))]
+    fn solve_in(
+        &self,
+        denomination: Denomination,
+        declarer: Position,
+        tt: &mut TranspositionTable,
+    ) -> u8 {
+        tt.entries.clear();
+        tt.nodes = 0;
         let trump = denomination.to_suit();
         let state = GameState::new(&self.deal, declarer, trump);
-        let mut tt = HashMap::new();
 
-        self.alpha_beta(&state, 0, state.num_tricks, &mut tt)
+        let tricks = self.alpha_beta(&state, 0, state.num_tricks, tt);
+        self.last_node_count.store(tt.nodes, Ordering::Relaxed);
+        tricks
     }
 
     /// Solve and return a play line that achieves the result (for debugging)
@@ -439,17 +851,120 @@ impl DoubleDummySolver {
     ) -> SolveResultWithLine {
         let trump = denomination.to_suit();
         let state = GameState::new(&self.deal, declarer, trump);
-        let mut tt = HashMap::new();
+        let mut tt = TranspositionTable::default();
 
         // First pass: find the optimal score
         let tricks = self.alpha_beta(&state, 0, state.num_tricks, &mut tt);
 
         // Second pass: find a line that achieves this score
         let play_line = self.find_line(&state, tricks, &mut tt);
+        self.last_node_count.store(tt.nodes, Ordering::Relaxed);
 
         SolveResultWithLine { tricks, play_line }
     }
 
+    /// Solve and return every opening lead that holds declarer to the result
+    ///
+    /// Each legal lead is re-searched with a null window at the optimum, so a
+    /// lead is included only if declarer can take no more than the returned
+    /// trick count after it.
+    pub fn solve_with_leads(
+        &self,
+        denomination: Denomination,
+        declarer: Position,
+    ) -> (u8, Vec<Card>) {
+        let trump = denomination.to_suit();
+        let state = GameState::new(&self.deal, declarer, trump);
+        let mut tt = TranspositionTable::default();
+
+        let tricks = self.alpha_beta(&state, 0, state.num_tricks, &mut tt);
+
+        let leads = state
+            .legal_moves()
+            .into_iter()
+            .filter(|&card| {
+                let mut new_state = state.clone();
+                new_state
+                    .play_card(card)
+                    .expect("search only plays legal moves");
+                self.alpha_beta(&new_state, tricks, tricks + 1, &mut tt) <= tricks
+            })
+            .collect();
+        self.last_node_count.store(tt.nodes, Ordering::Relaxed);
+
+        (tricks, leads)
+    }
+
+    /// Every legal card for the player on turn, with declarer's total tricks
+    /// after playing it
+    ///
+    /// Totals include tricks declarer's side has already won. Declarer's side
+    /// should pick the highest count and the defence the lowest. Empty once
+    /// all tricks have been played.
+    pub fn analyze_position(&self, state: &GameState) -> Vec<(Card, u8)> {
+        let mut tt = TranspositionTable::default();
+        let analysis = state
+            .legal_moves()
+            .into_iter()
+            .map(|card| {
+                let mut new_state = state.clone();
+                new_state
+                    .play_card(card)
+                    .expect("search only plays legal moves");
+                (
+                    card,
+                    self.alpha_beta(&new_state, 0, new_state.num_tricks, &mut tt),
+                )
+            })
+            .collect();
+        self.last_node_count.store(tt.nodes, Ordering::Relaxed);
+        analysis
+    }
+
+    /// Cheap (lower, upper) bounds on declarer's tricks without searching
+    ///
+    /// The upper bound gives the defence the top winners the opening leader
+    /// can cash before anyone can ruff, or its sure top trumps if that is
+    /// more. The lower bound counts declarer's side's sure top trumps, so it
+    /// is 0 in notrump. Both are conservative: `solve()` always lands within
+    /// them, which makes this suitable for discarding deals before an exact
+    /// solve.
+    pub fn fast_trick_estimate(&self, denomination: Denomination, declarer: Position) -> (u8, u8) {
+        let deal = &self.deal;
+        let total = deal.hand(declarer).len() as u8;
+        let leader = declarer.next();
+        let defence = [leader, leader.partner()];
+        let cashes = |suit: Suit| top_run(deal, suit, &[leader]).len() as u8;
+
+        let (lower, defence_tricks) = match denomination.to_suit() {
+            None => (0, Suit::ALL.iter().map(|&suit| cashes(suit)).sum()),
+            Some(trump) => {
+                // A side suit can only be cashed while every other hand that
+                // could ruff is still following
+                let ruffers: Vec<Position> = Position::ALL
+                    .into_iter()
+                    .filter(|&p| p != leader && deal.hand(p).suit_length(trump) > 0)
+                    .collect();
+                let side_cashes: u8 = Suit::ALL
+                    .iter()
+                    .filter(|&&suit| suit != trump)
+                    .map(|&suit| {
+                        ruffers
+                            .iter()
+                            .map(|&p| deal.hand(p).suit_length(suit) as u8)
+                            .fold(cashes(suit), u8::min)
+                    })
+                    .sum();
+                (
+                    sure_trump_tricks(deal, trump, [declarer, declarer.partner()]),
+                    (cashes(trump) + side_cashes).max(sure_trump_tricks(deal, trump, defence)),
+                )
+            }
+        };
+
+        (lower, total - defence_tricks.min(total))
+    }
+
     /// Find a concrete play line that achieves the target score
     fn find_line(
         &self,
@@ -480,11 +995,16 @@ impl DoubleDummySolver {
         }
 
         let maximizing = state.declarer_side_on_lead();
-        let moves = state.legal_moves();
+        // Try cards lowest index first, so the line found does not depend on
+        // the order cards were added to the hands
+        let mut moves = state.legal_moves();
+        moves.sort_by_key(|card| card.to_index());
 
         for card in moves {
             let mut new_state = state.clone();
-            new_state.play_card(card);
+            new_state
+                .play_card(card)
+                .expect("search only plays legal moves");
 
             // Check if this move can lead to target
             let score = self.alpha_beta(&new_state, alpha, beta, tt);
@@ -509,34 +1029,55 @@ impl DoubleDummySolver {
 
     /// Alpha-beta minimax search with transposition table
     fn alpha_beta(
+        &self,
+        state: &GameState,
+        alpha: u8,
+        beta: u8,
+        tt: &mut TranspositionTable,
+    ) -> u8 {
+        self.search(state, alpha, beta, tt, GameState::ordered_moves)
+    }
+
+    /// Alpha-beta search trying each position's moves in the order `moves` gives
+    ///
+    /// `moves` gets the state and whether declarer's side is on play.
+    fn search<M>(
         &self,
         state: &GameState,
         mut alpha: u8,
         mut beta: u8,
         tt: &mut TranspositionTable,
-    ) -> u8 {
+        moves: M,
+    ) -> u8
+    where
+        M: Fn(&GameState, bool) -> Vec<Card> + Copy,
+    {
+        tt.nodes += 1;
+
         // Terminal node
         if state.is_terminal() {
             return state.score();
         }
 
-        // TT lookup - only at trick boundaries for correctness
+        // TT lookup - only at trick boundaries for correctness. Entries hold
+        // the tricks still to come, so declarer's tricks so far are added back
+        let won = state.declarer_tricks;
         let hash = if state.at_trick_boundary() {
             let h = state.hash();
-            if let Some(&entry) = tt.get(&h) {
+            if let Some(&entry) = tt.entries.get(&h) {
                 match entry {
-                    TTEntry::Exact(v) => return v,
+                    TTEntry::Exact(v) => return won + v,
                     TTEntry::LowerBound(v) => {
-                        if v >= beta {
-                            return v;
+                        if won + v >= beta {
+                            return won + v;
                         }
-                        alpha = alpha.max(v);
+                        alpha = alpha.max(won + v);
                     }
                     TTEntry::UpperBound(v) => {
-                        if v <= alpha {
-                            return v;
+                        if won + v <= alpha {
+                            return won + v;
                         }
-                        beta = beta.min(v);
+                        beta = beta.min(won + v);
                     }
                 }
             }
@@ -546,15 +1087,16 @@ impl DoubleDummySolver {
         };
 
         let maximizing = state.declarer_side_on_lead();
-        let moves = state.legal_moves();
         let orig_alpha = alpha;
 
         let value = if maximizing {
             let mut value = 0u8;
-            for card in moves {
+            for card in moves(state, maximizing) {
                 let mut new_state = state.clone();
-                new_state.play_card(card);
-                let score = self.alpha_beta(&new_state, alpha, beta, tt);
+                new_state
+                    .play_card(card)
+                    .expect("search only plays legal moves");
+                let score = self.search(&new_state, alpha, beta, tt, moves);
                 value = value.max(score);
                 alpha = alpha.max(value);
                 if alpha >= beta {
@@ -564,10 +1106,12 @@ impl DoubleDummySolver {
             value
         } else {
             let mut value = state.num_tricks;
-            for card in moves {
+            for card in moves(state, maximizing) {
                 let mut new_state = state.clone();
-                new_state.play_card(card);
-                let score = self.alpha_beta(&new_state, alpha, beta, tt);
+                new_state
+                    .play_card(card)
+                    .expect("search only plays legal moves");
+                let score = self.search(&new_state, alpha, beta, tt, moves);
                 value = value.min(score);
                 beta = beta.min(value);
                 if alpha >= beta {
@@ -580,13 +1124,13 @@ impl DoubleDummySolver {
         // TT store - only at trick boundaries
         if let Some(h) = hash {
             let entry = if value <= orig_alpha {
-                TTEntry::UpperBound(value)
+                TTEntry::UpperBound(value - won)
             } else if value >= beta {
-                TTEntry::LowerBound(value)
+                TTEntry::LowerBound(value - won)
             } else {
-                TTEntry::Exact(value)
+                TTEntry::Exact(value - won)
             };
-            tt.insert(h, entry);
+            tt.entries.insert(h, entry);
         }
 
         value
@@ -618,6 +1162,118 @@ mod tests {
         assert_eq!(Denomination::NoTrump.to_char(), 'N');
     }
 
+    #[test]
+    fn test_play_card_errors() {
+        // Seed 1: East 652.AK42.AQ87.T4 leads against North, South J74.QT95.T.AK863
+        let mut gen = dealer_core::DealGenerator::new(1);
+        let deal = gen.generate();
+        let mut state = GameState::new(&deal, Position::North, None);
+
+        assert_eq!(
+            state.play_card(Card::new(Suit::Spades, Rank::Ace)),
+            Err(PlayError::NotInHand)
+        );
+        assert_eq!(state.play_card(Card::new(Suit::Hearts, Rank::Two)), Ok(()));
+        assert_eq!(
+            state.play_card(Card::new(Suit::Spades, Rank::Jack)),
+            Err(PlayError::MustFollowSuit)
+        );
+        assert_eq!(state.play_card(Card::new(Suit::Hearts, Rank::Five)), Ok(()));
+    }
+
+    #[test]
+    fn test_play_card_after_last_trick() {
+        let mut deal = Deal::new();
+        deal.hand_mut(Position::North)
+            .add_card(Card::new(Suit::Spades, Rank::Ace));
+        deal.hand_mut(Position::East)
+            .add_card(Card::new(Suit::Spades, Rank::King));
+        deal.hand_mut(Position::South)
+            .add_card(Card::new(Suit::Spades, Rank::Queen));
+        deal.hand_mut(Position::West)
+            .add_card(Card::new(Suit::Spades, Rank::Jack));
+
+        let mut state = GameState::new(&deal, Position::North, None);
+        for (rank, position) in [
+            (Rank::King, Position::East),
+            (Rank::Queen, Position::South),
+            (Rank::Jack, Position::West),
+            (Rank::Ace, Position::North),
+        ] {
+            assert_eq!(state.next_player(), position);
+            assert_eq!(state.play_card(Card::new(Suit::Spades, rank)), Ok(()));
+        }
+
+        assert!(state.is_terminal());
+        assert_eq!(state.score(), 1);
+        assert_eq!(
+            state.play_card(Card::new(Suit::Spades, Rank::Ace)),
+            Err(PlayError::GameOver)
+        );
+    }
+
+    #[test]
+    fn test_denomination_from_char() {
+        for denomination in Denomination::ALL {
+            let c = denomination.to_char();
+            assert_eq!(Denomination::from_char(c), Some(denomination));
+            assert_eq!(
+                Denomination::from_char(c.to_ascii_lowercase()),
+                Some(denomination)
+            );
+        }
+        assert_eq!(Denomination::from_char('X'), None);
+    }
+
+    #[test]
+    fn test_denomination_from_str() {
+        assert_eq!("C".parse(), Ok(Denomination::Clubs));
+        assert_eq!("d".parse(), Ok(Denomination::Diamonds));
+        assert_eq!("H".parse(), Ok(Denomination::Hearts));
+        assert_eq!("s".parse(), Ok(Denomination::Spades));
+        assert_eq!("N".parse(), Ok(Denomination::NoTrump));
+        assert_eq!("NT".parse(), Ok(Denomination::NoTrump));
+        assert_eq!("nt".parse(), Ok(Denomination::NoTrump));
+
+        assert!("".parse::<Denomination>().is_err());
+        assert!("ST".parse::<Denomination>().is_err());
+        assert!("NTT".parse::<Denomination>().is_err());
+        assert!("X".parse::<Denomination>().is_err());
+    }
+
+    #[test]
+    fn test_compact_round_trip() {
+        let mut result = DoubleDummyResult::new();
+        for (i, denomination) in Denomination::ALL.into_iter().enumerate() {
+            for (j, declarer) in Position::ALL.into_iter().enumerate() {
+                result.set_tricks(denomination, declarer, ((i * 4 + j) % 14) as u8);
+            }
+        }
+        result.set_tricks(Denomination::NoTrump, Position::North, 13);
+
+        let compact = result.to_compact();
+        assert_eq!(compact.len(), 20);
+        assert!(compact.starts_with('d'));
+        assert_eq!(
+            DoubleDummyResult::from_compact(&compact),
+            Ok(result.clone())
+        );
+        assert_eq!(
+            DoubleDummyResult::from_compact(&compact.to_uppercase()),
+            Ok(result)
+        );
+    }
+
+    #[test]
+    fn test_compact_rejects_bad_input() {
+        assert!(DoubleDummyResult::from_compact("").is_err());
+        assert!(DoubleDummyResult::from_compact("0123456789ABCD012345F").is_err());
+        assert!(DoubleDummyResult::from_compact("0123456789ABCD01234").is_err());
+        // E and F are hex but more than 13 tricks
+        assert!(DoubleDummyResult::from_compact("0123456789ABCDE01234").is_err());
+        assert!(DoubleDummyResult::from_compact("0123456789ABCD01234X").is_err());
+    }
+
     #[test]
     fn test_double_dummy_result() {
         let mut result = DoubleDummyResult::new();
@@ -625,6 +1281,70 @@ mod tests {
         assert_eq!(result.get_tricks(Denomination::Spades, Position::North), 10);
     }
 
+    #[test]
+    fn test_side_aggregates() {
+        let mut result = DoubleDummyResult::new();
+        // N and S differ in hearts; E and W differ in clubs
+        result.set_tricks(Denomination::Hearts, Position::North, 9);
+        result.set_tricks(Denomination::Hearts, Position::South, 10);
+        result.set_tricks(Denomination::Hearts, Position::East, 3);
+        result.set_tricks(Denomination::Hearts, Position::West, 3);
+        result.set_tricks(Denomination::Clubs, Position::North, 6);
+        result.set_tricks(Denomination::Clubs, Position::South, 6);
+        result.set_tricks(Denomination::Clubs, Position::East, 7);
+        result.set_tricks(Denomination::Clubs, Position::West, 5);
+        result.set_tricks(Denomination::NoTrump, Position::North, 10);
+        result.set_tricks(Denomination::NoTrump, Position::South, 8);
+
+        assert_eq!(result.ns_tricks(Denomination::Hearts), 10);
+        assert_eq!(result.ew_tricks(Denomination::Hearts), 3);
+        assert_eq!(result.ns_tricks(Denomination::Clubs), 6);
+        assert_eq!(result.ew_tricks(Denomination::Clubs), 7);
+
+        // Hearts and NT both give 10 tricks; NT ranks higher
+        assert_eq!(result.best_ns_contract(), (Denomination::NoTrump, 10));
+
+        result.set_tricks(Denomination::Spades, Position::South, 11);
+        assert_eq!(result.best_ns_contract(), (Denomination::Spades, 11));
+    }
+
+    #[test]
+    fn test_ranked_and_makeable() {
+        let mut result = DoubleDummyResult::new();
+        result.set_tricks(Denomination::Hearts, Position::South, 10);
+        result.set_tricks(Denomination::NoTrump, Position::North, 9);
+        result.set_tricks(Denomination::NoTrump, Position::South, 9);
+        result.set_tricks(Denomination::Spades, Position::East, 9);
+        result.set_tricks(Denomination::Clubs, Position::West, 8);
+
+        let ranked = result.ranked();
+        assert_eq!(ranked.len(), 20);
+        let top: Vec<(Denomination, Position, u8)> = ranked[..5]
+            .iter()
+            .map(|r| (r.denomination, r.declarer, r.tricks))
+            .collect();
+        assert_eq!(
+            top,
+            vec![
+                (Denomination::Hearts, Position::South, 10),
+                (Denomination::NoTrump, Position::North, 9),
+                (Denomination::NoTrump, Position::South, 9),
+                (Denomination::Spades, Position::East, 9),
+                (Denomination::Clubs, Position::West, 8),
+            ]
+        );
+        // The zero-trick remainder starts with the highest denomination
+        assert_eq!(ranked[5].denomination, Denomination::NoTrump);
+        assert_eq!(ranked[5].declarer, Position::East);
+        assert!(ranked.windows(2).all(|w| w[0].tricks >= w[1].tricks));
+
+        // Level 3 needs exactly 9 tricks; 8 falls short
+        assert_eq!(result.makeable(3), ranked[..4].to_vec());
+        assert_eq!(result.makeable(4), ranked[..1].to_vec());
+        assert_eq!(result.makeable(2).len(), 5);
+        assert!(result.makeable(5).is_empty());
+    }
+
     /// Create a simple deal where each hand has one suit (fast to solve)
     fn create_simple_deal() -> Deal {
         let ranks = [
@@ -662,6 +1382,350 @@ mod tests {
         deal
     }
 
+    /// Each hand's top `cards` cards from the deal generated by `seed`, highest first
+    fn seeded_ending(seed: u32, cards: usize) -> Deal {
+        let full = dealer_core::DealGenerator::new(seed).generate();
+        let mut deal = Deal::new();
+        for position in Position::ALL {
+            let mut held = full.hand(position).cards().to_vec();
+            held.sort_by_key(|card| Reverse(card.rank));
+            deal.hand_mut(position).add_cards(&held[..cards]);
+        }
+        deal
+    }
+
+    #[test]
+    fn test_solve_with_leads() {
+        // Three-card ending in NT with South declaring, so West is on lead.
+        // Only a spade to East's winners holds declarer to no tricks.
+        let mut deal = Deal::new();
+        let holdings = [
+            (
+                Position::North,
+                Suit::Hearts,
+                [Rank::Ace, Rank::King, Rank::Queen],
+            ),
+            (
+                Position::East,
+                Suit::Spades,
+                [Rank::Ace, Rank::King, Rank::Queen],
+            ),
+            (
+                Position::South,
+                Suit::Diamonds,
+                [Rank::Ace, Rank::King, Rank::Queen],
+            ),
+        ];
+        for (position, suit, ranks) in holdings {
+            for rank in ranks {
+                deal.hand_mut(position).add_card(Card::new(suit, rank));
+            }
+        }
+        for suit in [Suit::Spades, Suit::Hearts, Suit::Diamonds] {
+            deal.hand_mut(Position::West)
+                .add_card(Card::new(suit, Rank::Two));
+        }
+
+        let solver = DoubleDummySolver::new(deal);
+        let (tricks, leads) = solver.solve_with_leads(Denomination::NoTrump, Position::South);
+        assert_eq!(tricks, 0);
+        assert_eq!(leads, vec![Card::new(Suit::Spades, Rank::Two)]);
+        assert_eq!(tricks, solver.solve(Denomination::NoTrump, Position::South));
+    }
+
+    #[test]
+    fn test_analyze_position() {
+        // Seed 1 cut down to each hand's top five cards, South declaring spades
+        let deal = seeded_ending(1, 5);
+        let solver = DoubleDummySolver::new(deal.clone());
+        let tricks = solver.solve(Denomination::Spades, Position::South);
+
+        // West is on lead, so the defence's best lead gives the solved count
+        let start =
+            GameState::from_played(&deal, Position::South, Denomination::Spades, &[]).unwrap();
+        let leads = solver.analyze_position(&start);
+        assert_eq!(leads.len(), 5);
+        assert_eq!(leads.iter().map(|&(_, t)| t).min(), Some(tricks));
+
+        // After each lead, North's best reply keeps the count for that lead
+        for &(lead, after_lead) in &leads {
+            let state = GameState::from_played(
+                &deal,
+                Position::South,
+                Denomination::Spades,
+                &[(Position::West, lead)],
+            )
+            .unwrap();
+            let replies = solver.analyze_position(&state);
+            assert_eq!(
+                replies.iter().map(|&(_, t)| t).max(),
+                Some(after_lead),
+                "after {:?}",
+                lead
+            );
+        }
+
+        // North cannot lead out of turn
+        let north_card = deal.hand(Position::North).cards()[0];
+        assert_eq!(
+            GameState::from_played(
+                &deal,
+                Position::South,
+                Denomination::Spades,
+                &[(Position::North, north_card)]
+            )
+            .err(),
+            Some(PlayError::OutOfTurn(Position::West))
+        );
+    }
+
+    #[test]
+    fn test_from_played_prefix() {
+        // Seed 1, North declaring notrump: East leads H2, South and West follow
+        let deal = dealer_core::DealGenerator::new(1).generate();
+        let mut played = vec![
+            (Position::East, Card::new(Suit::Hearts, Rank::Two)),
+            (Position::South, Card::new(Suit::Hearts, Rank::Five)),
+            (Position::West, Card::new(Suit::Hearts, Rank::Three)),
+        ];
+        let state =
+            GameState::from_played(&deal, Position::North, Denomination::NoTrump, &played).unwrap();
+        assert_eq!(state.next_player(), Position::North);
+        assert_eq!(state.tricks_played(), 0);
+
+        // North's jack wins the trick and leads to the next
+        played.push((Position::North, Card::new(Suit::Hearts, Rank::Jack)));
+        let state =
+            GameState::from_played(&deal, Position::North, Denomination::NoTrump, &played).unwrap();
+        assert_eq!(state.next_player(), Position::North);
+        assert_eq!(state.tricks_played(), 1);
+
+        // South cannot discard a spade while holding hearts
+        played[1].1 = Card::new(Suit::Spades, Rank::Jack);
+        assert_eq!(
+            GameState::from_played(&deal, Position::North, Denomination::NoTrump, &played).err(),
+            Some(PlayError::MustFollowSuit)
+        );
+    }
+
+    #[test]
+    fn test_notrump_opening_leader_each_declarer() {
+        // One-card ending: N SA, E HA, S SK, W S2. Whoever leads decides the
+        // trick, so a wrong opening leader would change the count.
+        let mut deal = Deal::new();
+        for (position, suit, rank) in [
+            (Position::North, Suit::Spades, Rank::Ace),
+            (Position::East, Suit::Hearts, Rank::Ace),
+            (Position::South, Suit::Spades, Rank::King),
+            (Position::West, Suit::Spades, Rank::Two),
+        ] {
+            deal.hand_mut(position).add_card(Card::new(suit, rank));
+        }
+        let solver = DoubleDummySolver::new(deal.clone());
+
+        // East cashes HA against North; West must lead into SA-K against
+        // South; South and North take the trick against East and West
+        for (declarer, expected) in [
+            (Position::North, 0),
+            (Position::East, 0),
+            (Position::South, 1),
+            (Position::West, 0),
+        ] {
+            let (tricks, leads) = solver.solve_with_leads(Denomination::NoTrump, declarer);
+            assert_eq!(tricks, expected, "{:?} declaring", declarer);
+            assert_eq!(tricks, solver.solve(Denomination::NoTrump, declarer));
+
+            // The opening lead comes from declarer's left
+            assert!(!leads.is_empty());
+            let leader = declarer.next();
+            assert!(
+                leads.iter().all(|&card| deal.hand(leader).has_card(card)),
+                "{:?} declaring: leads {:?} not from {:?}",
+                declarer,
+                leads,
+                leader
+            );
+        }
+    }
+
+    #[test]
+    fn test_play_line_is_reproducible() {
+        // Seed 1 cut down to each hand's top four cards, in two card orders
+        let high_first = seeded_ending(1, 4);
+        let mut low_first = Deal::new();
+        for position in Position::ALL {
+            let mut cards = high_first.hand(position).cards().to_vec();
+            cards.reverse();
+            low_first.hand_mut(position).add_cards(&cards);
+        }
+
+        let solver = DoubleDummySolver::new(high_first);
+        let line = solver.solve_with_line(Denomination::NoTrump, Position::South);
+        assert_eq!(line.play_line.len(), 16);
+
+        let again = solver.solve_with_line(Denomination::NoTrump, Position::South);
+        assert_eq!(again.play_line, line.play_line);
+
+        let reordered = DoubleDummySolver::new(low_first)
+            .solve_with_line(Denomination::NoTrump, Position::South);
+        assert_eq!(reordered.tricks, line.tricks);
+        assert_eq!(reordered.play_line, line.play_line);
+    }
+
+    #[test]
+    fn test_last_node_count() {
+        // Deal the lowest 4 * n cards round-robin so every hand has n mixed-suit cards
+        let ending = |n: u8| {
+            let mut deal = Deal::new();
+            for i in 0..4 * n {
+                deal.hand_mut(Position::ALL[(i % 4) as usize])
+                    .add_card(Card::from_index(i).unwrap());
+            }
+            DoubleDummySolver::new(deal)
+        };
+
+        let one_trick = ending(1);
+        assert_eq!(one_trick.last_node_count(), 0);
+        one_trick.solve(Denomination::NoTrump, Position::South);
+        let small = one_trick.last_node_count();
+        assert!(small > 0);
+
+        let six_tricks = ending(6);
+        six_tricks.solve(Denomination::NoTrump, Position::South);
+        assert!(six_tricks.last_node_count() > small);
+    }
+
+    #[test]
+    fn test_transposition_key_is_exact() {
+        // East's club king sits over North's ace-queen and always scores. The
+        // old XOR hash gave two positions of this ending one entry, and with
+        // ordered moves the search returned 0.
+        let deal = dealer_pbn::parse_deal_tag("[Deal \"N:A...AQ ..AK.K J.KQ.. KQ.A..\"]")
+            .unwrap()
+            .deal;
+        let solver = DoubleDummySolver::new(deal);
+
+        assert_eq!(solver.solve(Denomination::Clubs, Position::East), 1);
+        for denomination in Denomination::ALL {
+            for declarer in Position::ALL {
+                assert_eq!(
+                    solver.solve(denomination, declarer),
+                    solve_unordered(&solver, denomination, declarer).0,
+                    "{:?} by {:?}",
+                    denomination,
+                    declarer
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_move_ordering_prunes() {
+        // Seed 1 cut down to each hand's top six cards. solve_in() pins the
+        // built-in search even when solves go to the engine
+        let solver = DoubleDummySolver::new(seeded_ending(1, 6));
+        for denomination in [Denomination::NoTrump, Denomination::Spades] {
+            let tricks = solver.solve_in(
+                denomination,
+                Position::South,
+                &mut TranspositionTable::default(),
+            );
+            let ordered_nodes = solver.last_node_count();
+            let (unordered_tricks, unordered_nodes) =
+                solve_unordered(&solver, denomination, Position::South);
+            assert_eq!(unordered_tricks, tricks);
+            assert!(
+                ordered_nodes < unordered_nodes,
+                "{:?}: {} nodes ordered vs {} unordered",
+                denomination,
+                ordered_nodes,
+                unordered_nodes
+            );
+        }
+    }
+
+    /// Solve trying moves in raw hand order; returns tricks and nodes searched
+    fn solve_unordered(
+        solver: &DoubleDummySolver,
+        denomination: Denomination,
+        declarer: Position,
+    ) -> (u8, u64) {
+        let state = GameState::new(&solver.deal, declarer, denomination.to_suit());
+        let mut tt = TranspositionTable::default();
+        let tricks = solver.search(&state, 0, state.num_tricks, &mut tt, |state, _| {
+            state.legal_moves()
+        });
+        (tricks, tt.nodes)
+    }
+
+    #[test]
+    fn test_fast_trick_estimate_bounds_exact_solve() {
+        // Each hand's top five cards from a few seeded deals
+        for seed in 1..=4 {
+            let solver = DoubleDummySolver::new(seeded_ending(seed, 5));
+            for denomination in [
+                Denomination::NoTrump,
+                Denomination::Spades,
+                Denomination::Clubs,
+            ] {
+                for declarer in [Position::North, Position::East] {
+                    let (lower, upper) = solver.fast_trick_estimate(denomination, declarer);
+                    let tricks = solver.solve(denomination, declarer);
+                    assert!(
+                        lower <= tricks && tricks <= upper,
+                        "seed {} {:?} by {:?}: {} outside {}..={}",
+                        seed,
+                        denomination,
+                        declarer,
+                        tricks,
+                        lower,
+                        upper
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_table_matches_independent_solves() {
+        // Each hand's top five cards, so the 20 solves stay quick
+        for seed in 1..=2 {
+            let solver = DoubleDummySolver::new(seeded_ending(seed, 5));
+            let table = solver.solve_table();
+            let result = solver.solve_all();
+            for denomination in Denomination::ALL {
+                for declarer in Position::ALL {
+                    let tricks = solver.solve(denomination, declarer);
+                    assert_eq!(
+                        table[denomination as usize][declarer as usize], tricks,
+                        "seed {} {:?} by {:?}",
+                        seed, denomination, declarer
+                    );
+                    assert_eq!(result.get_tricks(denomination, declarer), tricks);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fast_trick_estimate_one_suit_hands() {
+        // Exact without searching: East cashes 13 hearts, North holds every trump
+        let solver = DoubleDummySolver::new(create_simple_deal());
+        assert_eq!(
+            solver.fast_trick_estimate(Denomination::NoTrump, Position::North),
+            (0, 0)
+        );
+        assert_eq!(
+            solver.fast_trick_estimate(Denomination::Spades, Position::North),
+            (13, 13)
+        );
+        // With diamonds trump South ruffs the hearts, so East cashes nothing
+        assert_eq!(
+            solver.fast_trick_estimate(Denomination::Diamonds, Position::North),
+            (13, 13)
+        );
+    }
+
     #[test]
     #[ignore] // Slow: runs DDS solver 20 times
     fn test_solver_creation() {
@@ -687,6 +1751,37 @@ mod tests {
         assert_eq!(tricks_spades, 13);
     }
 
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn test_solve_all_parallel_matches_sequential() {
+        // Four cards per hand keeps the 40 solves quick
+        let solver = DoubleDummySolver::new(seeded_ending(3, 4));
+        assert_eq!(solver.solve_all_parallel(), solver.solve_all());
+    }
+
+    #[test]
+    #[cfg(feature = "solver2")]
+    fn test_engine_matches_builtin_search() {
+        // A four-card ending where the lead, trumps and entries all matter
+        let deal = dealer_pbn::parse_deal_tag("[Deal \"N:AK.Q.J.T QJ.A.K.9 T9.K.A.Q 87.J.Q.A\"]")
+            .unwrap()
+            .deal;
+        let solver = DoubleDummySolver::new(deal);
+        for denomination in Denomination::ALL {
+            for declarer in Position::ALL {
+                let builtin =
+                    solver.solve_in(denomination, declarer, &mut TranspositionTable::default());
+                assert_eq!(
+                    solver.solve(denomination, declarer),
+                    builtin,
+                    "{:?} by {:?}",
+                    denomination,
+                    declarer
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_trick_winner() {
         let mut trick = TrickState::new(Position::North, Some(Suit::Spades));
@@ -0,0 +1,286 @@
+//! Double-dummy results in PBN and text output
+//!
+//! These formatters solve deals, so they live here rather than in dealer-pbn,
+//! which stays free of the solver.
+
+use crate::{Denomination, DoubleDummyResult, DoubleDummySolver};
+use dealer_core::{Deal, Position, Side, Vulnerability};
+use dealer_pbn::format_printpbn;
+
+/// Format a deal in PBN format with double-dummy analysis appended
+///
+/// Same as [`format_printpbn`], plus the `[DoubleDummyTricks]`, `[OptimumScore]`,
+/// `[ParContract]` and `[OptimumResultTable]` tags. This runs the double-dummy
+/// solver for all 20 declarer/denomination combinations, so it is much slower.
+pub fn format_printpbn_with_dds(
+    deal: &Deal,
+    board_number: usize,
+    dealer: Option<Position>,
+    vulnerability: Option<Vulnerability>,
+    event_name: Option<&str>,
+    seed: Option<u32>,
+    input_file: Option<&str>,
+) -> String {
+    let mut result = format_printpbn(
+        deal,
+        board_number,
+        dealer,
+        vulnerability,
+        event_name,
+        seed,
+        input_file,
+    );
+
+    // Insert the DDS tags before the blank line that ends the board
+    result.pop();
+
+    let dds = DoubleDummySolver::new(deal.clone()).solve_all();
+    let vuln = vulnerability.unwrap_or_else(|| Vulnerability::for_board(board_number + 1));
+    result.push_str(&format_dds_tags(&dds, vuln));
+    result.push('\n');
+
+    result
+}
+
+/// Declarer order used by the PBN double-dummy tags
+const DDS_DECLARERS: [Position; 4] = [
+    Position::North,
+    Position::South,
+    Position::East,
+    Position::West,
+];
+
+/// Denomination order used by the PBN double-dummy tags
+const DDS_DENOMINATIONS: [Denomination; 5] = [
+    Denomination::NoTrump,
+    Denomination::Spades,
+    Denomination::Hearts,
+    Denomination::Diamonds,
+    Denomination::Clubs,
+];
+
+/// Format the double-dummy and par tags for a solved deal
+fn format_dds_tags(dds: &DoubleDummyResult, vuln: Vulnerability) -> String {
+    let mut result = String::new();
+
+    // 20 hex digits: declarers N, S, E, W, each in NT, S, H, D, C order
+    result.push_str(&format!("[DoubleDummyTricks \"{}\"]\n", dds.to_compact()));
+
+    // Par score (NS point of view) and the contracts that achieve it
+    let par = dds.par(vuln);
+    result.push_str(&format!("[OptimumScore \"NS {}\"]\n", par.score));
+
+    let mut contracts: Vec<String> = Vec::new();
+    for contract in &par.contracts {
+        let text = format!(
+            "{}{}{}",
+            contract.level,
+            denomination_str(contract.denomination),
+            if contract.doubled { "x" } else { "" }
+        );
+        let seat = contract.declarer.to_char();
+        // Merge partners declaring the same contract (e.g. "NS 3NT")
+        match contracts
+            .iter_mut()
+            .find(|c| c.ends_with(&format!(" {}", text)))
+        {
+            Some(existing) => existing.insert(1, seat),
+            None => contracts.push(format!("{} {}", seat, text)),
+        }
+    }
+    let par_contract = if contracts.is_empty() {
+        "Pass".to_string()
+    } else {
+        contracts.join(", ")
+    };
+    result.push_str(&format!("[ParContract \"{}\"]\n", par_contract));
+
+    // Full trick table
+    result.push_str("[OptimumResultTable \"Declarer;Denomination\\2R;Result\\2R\"]\n");
+    for declarer in DDS_DECLARERS {
+        for denom in DDS_DENOMINATIONS {
+            result.push_str(&format!(
+                "{} {} {}\n",
+                declarer.to_char(),
+                denomination_str(denom),
+                dds.get_tricks(denom, declarer)
+            ));
+        }
+    }
+
+    result
+}
+
+/// Get the PBN string for a denomination
+fn denomination_str(denom: Denomination) -> &'static str {
+    match denom {
+        Denomination::Clubs => "C",
+        Denomination::Diamonds => "D",
+        Denomination::Hearts => "H",
+        Denomination::Spades => "S",
+        Denomination::NoTrump => "NT",
+    }
+}
+
+/// Solve a deal and format its double-dummy table, one line per declarer
+///
+/// Declarers and denominations follow the PBN tag order. Example output:
+/// ```text
+///        NT  S  H  D  C
+/// North   0 13  0 13  0
+/// South   0 13  0 13  0
+/// East    0  0 13  0 13
+/// West    0  0 13  0 13
+/// ```
+pub fn format_dd_table(deal: &Deal) -> String {
+    let dds = DoubleDummySolver::new(deal.clone()).solve_all();
+    let mut result = String::from("      ");
+
+    for denom in DDS_DENOMINATIONS {
+        result.push_str(&format!("{:>3}", denomination_str(denom)));
+    }
+    result.push('\n');
+
+    for declarer in DDS_DECLARERS {
+        let name = match declarer {
+            Position::North => "North",
+            Position::East => "East",
+            Position::South => "South",
+            Position::West => "West",
+        };
+        result.push_str(&format!("{:<6}", name));
+        for denom in DDS_DENOMINATIONS {
+            result.push_str(&format!("{:>3}", dds.get_tricks(denom, declarer)));
+        }
+        result.push('\n');
+    }
+
+    result
+}
+
+/// List the contracts each side makes double-dummy, e.g. `NS: 4H 3NT  EW: -`
+///
+/// Each denomination appears once per side at the highest level either
+/// partner makes, most tricks first (ties highest denomination first, as
+/// `DoubleDummyResult::ranked()`). A side that makes nothing shows `-`.
+pub fn format_makeable_contracts(dds: &DoubleDummyResult) -> String {
+    let makeable = dds.makeable(1);
+    let side_list = |side: Side| {
+        let mut seen = Vec::new();
+        let mut contracts = Vec::new();
+        for result in &makeable {
+            if side.positions().contains(&result.declarer) && !seen.contains(&result.denomination) {
+                seen.push(result.denomination);
+                contracts.push(format!(
+                    "{}{}",
+                    result.tricks - 6,
+                    denomination_str(result.denomination)
+                ));
+            }
+        }
+        if contracts.is_empty() {
+            "-".to_string()
+        } else {
+            contracts.join(" ")
+        }
+    };
+    format!("NS: {}  EW: {}\n", side_list(Side::NS), side_list(Side::EW))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dealer_pbn::parse_deal_tag;
+
+    #[test]
+    fn test_format_dd_table() {
+        // A three-card ending: North holds the top spades, East hearts, South
+        // diamonds, West clubs. Whoever holds trumps ruffs the opening lead and
+        // draws the rest; in NT the opening leader runs their suit.
+        let deal = parse_deal_tag("[Deal \"N:AKQ... .AKQ.. ..AKQ. ...AKQ\"]")
+            .unwrap()
+            .deal;
+
+        assert_eq!(
+            format_dd_table(&deal),
+            "       NT  S  H  D  C\n\
+             North   0  3  0  3  0\n\
+             South   0  3  0  3  0\n\
+             East    0  0  3  0  3\n\
+             West    0  0  3  0  3\n"
+        );
+    }
+
+    #[test]
+    fn test_format_makeable_contracts() {
+        // North/South hold the top spades and diamonds, East/West hearts and clubs,
+        // three of each in a six-card ending
+        let deal = parse_deal_tag("[Deal \"N:AKQ..AKQ. .AKQ..AKQ JT9..JT9. .JT9..JT9\"]")
+            .unwrap()
+            .to_ending()
+            .unwrap();
+        let dds = DoubleDummySolver::new(deal).solve_all();
+
+        // Nothing reaches seven tricks in a six-card ending
+        assert_eq!(format_makeable_contracts(&dds), "NS: -  EW: -\n");
+
+        // A made-up table: NS make 10 tricks in hearts and 9 in NT, EW nothing
+        let mut dds = DoubleDummyResult::new();
+        dds.set_tricks(Denomination::Hearts, Position::South, 10);
+        dds.set_tricks(Denomination::Hearts, Position::North, 9);
+        dds.set_tricks(Denomination::NoTrump, Position::North, 9);
+        dds.set_tricks(Denomination::Clubs, Position::East, 6);
+        assert_eq!(format_makeable_contracts(&dds), "NS: 4H 3NT  EW: -\n");
+        dds.set_tricks(Denomination::Clubs, Position::West, 8);
+        assert_eq!(format_makeable_contracts(&dds), "NS: 4H 3NT  EW: 2C\n");
+    }
+
+    /// Build a DDS table where NS take `ns` tricks and EW take `13 - ns` in every denomination
+    fn uniform_dds(ns: [u8; 5]) -> DoubleDummyResult {
+        let mut dds = DoubleDummyResult::new();
+        for (i, denom) in Denomination::ALL.iter().enumerate() {
+            dds.set_tricks(*denom, Position::North, ns[i]);
+            dds.set_tricks(*denom, Position::South, ns[i]);
+            dds.set_tricks(*denom, Position::East, 13 - ns[i]);
+            dds.set_tricks(*denom, Position::West, 13 - ns[i]);
+        }
+        dds
+    }
+
+    #[test]
+    fn test_dds_tags_decode() {
+        // C, D, H, S, NT
+        let mut dds = uniform_dds([8, 8, 7, 7, 9]);
+        dds.set_tricks(Denomination::Spades, Position::South, 6);
+        let output = format_dds_tags(&dds, Vulnerability::None);
+
+        let line = output
+            .lines()
+            .find(|l| l.starts_with("[DoubleDummyTricks "))
+            .unwrap();
+        let digits = &line[20..line.len() - 2];
+        assert_eq!(digits, "97788967884665546655");
+        assert_eq!(digits.len(), 20);
+
+        // Decode back and compare with the table
+        let chars: Vec<char> = digits.chars().collect();
+        for (i, declarer) in DDS_DECLARERS.iter().enumerate() {
+            for (j, denom) in DDS_DENOMINATIONS.iter().enumerate() {
+                let tricks = chars[i * 5 + j].to_digit(16).unwrap() as u8;
+                assert_eq!(tricks, dds.get_tricks(*denom, *declarer));
+            }
+        }
+    }
+
+    #[test]
+    fn test_dds_tags_par() {
+        // NS make 3NT non-vulnerable
+        let dds = uniform_dds([8, 8, 7, 7, 9]);
+        let output = format_dds_tags(&dds, Vulnerability::None);
+        assert!(output.contains("[OptimumScore \"NS 400\"]"));
+        assert!(output.contains("[ParContract \"NS 3NT\"]"));
+        assert!(output.contains("[OptimumResultTable \"Declarer;Denomination\\2R;Result\\2R\"]"));
+        assert!(output.contains("\nN NT 9\n"));
+        assert!(output.contains("\nW C 5\n"));
+    }
+}
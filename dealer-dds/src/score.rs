@@ -0,0 +1,488 @@
+//! Contract scoring and par computation
+//!
+//! Implements the duplicate scoring table, the par contract search used by
+//! `DoubleDummyResult::par()`, and IMP and matchpoint comparisons of scores.
+
+use crate::{Denomination, DoubleDummyResult};
+use dealer_core::{Position, Vulnerability};
+
+/// Number of distinct contracts from 1C to 7NT
+const NUM_CONTRACTS: usize = 35;
+
+/// Smallest point difference worth 1, 2, ... 24 IMPs (WBF scale)
+const IMP_THRESHOLDS: [i32; 24] = [
+    20, 50, 90, 130, 170, 220, 270, 320, 370, 430, 500, 600, 750, 900, 1100, 1300, 1500, 1750,
+    2000, 2250, 2500, 3000, 3500, 4000,
+];
+
+/// Smallest point difference worth 1, 2, ... 24 IMPs in DealerV2_4's table
+///
+/// Each entry is the WBF threshold less 10 except 410 (WBF 430) and 1190
+/// (WBF 1300), so this scale is one IMP higher at 10-19, 40-49, ... and two
+/// ranges (410-429 and 1190-1299) are further out of step.
+const DEALER_IMP_THRESHOLDS: [i32; 24] = [
+    10, 40, 80, 120, 160, 210, 260, 310, 360, 410, 490, 590, 740, 890, 1090, 1190, 1490, 1740,
+    1990, 2240, 2490, 2990, 3490, 3990,
+];
+
+/// A contract that achieves the par score
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParContract {
+    pub level: u8, // 1-7
+    pub denomination: Denomination,
+    pub declarer: Position,
+    /// True for a doubled sacrifice that goes down
+    pub doubled: bool,
+    /// Tricks taken by declarer
+    pub tricks: u8,
+}
+
+/// Par score and the contract(s) that achieve it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParResult {
+    /// Par score from the North-South point of view (0 if the deal is passed out)
+    pub score: i32,
+    /// All contracts that achieve the par score (empty if passed out)
+    pub contracts: Vec<ParContract>,
+}
+
+/// Calculate the duplicate score for a contract from declarer's point of view
+///
+/// Failing contracts score the undertrick penalty (negative). Only undoubled and
+/// doubled contracts are needed for par, so redoubles are not supported.
+pub fn contract_score(
+    level: u8,
+    denomination: Denomination,
+    doubled: bool,
+    vulnerable: bool,
+    tricks: u8,
+) -> i32 {
+    let tricks_needed = level as i32 + 6;
+    let result = tricks as i32 - tricks_needed;
+
+    if result < 0 {
+        let undertricks = -result;
+        if !doubled {
+            return -undertricks * if vulnerable { 100 } else { 50 };
+        }
+        let penalty = if vulnerable {
+            // 200 for the first, 300 for each subsequent
+            200 + (undertricks - 1) * 300
+        } else {
+            // 100, 200, 200, then 300 for each subsequent
+            match undertricks {
+                1 => 100,
+                2 => 300,
+                3 => 500,
+                n => 500 + (n - 3) * 300,
+            }
+        };
+        return -penalty;
+    }
+
+    let trick_value = match denomination {
+        Denomination::Clubs | Denomination::Diamonds => 20,
+        _ => 30,
+    };
+    let first_trick_bonus = if denomination == Denomination::NoTrump {
+        10
+    } else {
+        0
+    };
+    let mut trick_score = level as i32 * trick_value + first_trick_bonus;
+    if doubled {
+        trick_score *= 2;
+    }
+
+    let mut score = trick_score;
+
+    // Game or partscore bonus
+    score += if trick_score >= 100 {
+        if vulnerable {
+            500
+        } else {
+            300
+        }
+    } else {
+        50
+    };
+
+    // Slam bonuses
+    score += match (level, vulnerable) {
+        (6, false) => 500,
+        (6, true) => 750,
+        (7, false) => 1000,
+        (7, true) => 1500,
+        _ => 0,
+    };
+
+    // Overtricks and the insult for making a doubled contract
+    if doubled {
+        score += result * if vulnerable { 200 } else { 100 };
+        score += 50;
+    } else {
+        score += result * trick_value;
+    }
+
+    score
+}
+
+/// IMPs won by `score_a` against `score_b` on the standard scale
+///
+/// Both scores are from the same side's point of view; the result is negative
+/// when `score_b` is better. A 420-point difference is 9 IMPs, 430 is 10.
+///
+/// Uses the WBF scale. The filter language's `imps()` uses [`dealer_imps`]
+/// instead, which gives 10 IMPs for 420.
+pub fn imp_diff(score_a: i32, score_b: i32) -> i32 {
+    imps_on_scale(&IMP_THRESHOLDS, score_a - score_b)
+}
+
+/// IMPs for a score difference on DealerV2_4's table, as `imps()` reports them
+///
+/// Kept for compatibility with dealer scripts: 10 points is already 1 IMP
+/// and 410 is 10, one IMP above the WBF scale used by [`imp_diff`] for most
+/// differences. The sign of `score_diff` is preserved.
+pub fn dealer_imps(score_diff: i32) -> i32 {
+    imps_on_scale(&DEALER_IMP_THRESHOLDS, score_diff)
+}
+
+fn imps_on_scale(thresholds: &[i32; 24], diff: i32) -> i32 {
+    let imps = thresholds
+        .iter()
+        .take_while(|&&threshold| diff.abs() >= threshold)
+        .count() as i32;
+    imps * diff.signum()
+}
+
+/// Matchpoints for `my_score` against the other results on a board
+///
+/// Scores one point for each score beaten and half a point for each tie, so
+/// a top is `others.len()` and the result is always a multiple of 0.5.
+pub fn matchpoints(my_score: i32, others: &[i32]) -> f64 {
+    others
+        .iter()
+        .map(|&other| match my_score.cmp(&other) {
+            std::cmp::Ordering::Greater => 1.0,
+            std::cmp::Ordering::Equal => 0.5,
+            std::cmp::Ordering::Less => 0.0,
+        })
+        .sum()
+}
+
+/// Compute the par score and contracts for a complete double-dummy table
+///
+/// The auction is modelled as a game where each side may overcall the current
+/// contract or pass, and a contract that goes down is always doubled. When the
+/// result depends on who bids first (only when both sides can make the same
+/// contract), North-South are assumed to bid first.
+pub(crate) fn par(result: &DoubleDummyResult, vulnerability: Vulnerability) -> ParResult {
+    // final_score[k][side]: NS-relative score if `side` (0 = NS, 1 = EW)
+    // plays contract k with their best declarer
+    let mut final_score = [[0i32; 2]; NUM_CONTRACTS];
+    for (k, scores) in final_score.iter_mut().enumerate() {
+        let (level, denomination) = contract_at(k);
+        for (side, score) in scores.iter_mut().enumerate() {
+            let declarer = best_declarer(result, denomination, side);
+            let tricks = result.get_tricks(denomination, declarer);
+            let vulnerable = vulnerability.is_vulnerable(declarer);
+            let made = tricks as i32 >= level as i32 + 6;
+            let declarer_score = contract_score(level, denomination, !made, vulnerable, tricks);
+            *score = if side == 0 {
+                declarer_score
+            } else {
+                -declarer_score
+            };
+        }
+    }
+
+    // value[k][side]: NS-relative outcome once `side` has bid contract k and the
+    // opponents choose between passing and overcalling
+    let mut value = [[0i32; 2]; NUM_CONTRACTS];
+    // best_above[side]: best outcome for `side` among its own bids above k
+    let mut best_above: [Option<i32>; 2] = [None, None];
+    for k in (0..NUM_CONTRACTS).rev() {
+        for side in 0..2 {
+            let opponents = 1 - side;
+            let pass = final_score[k][side];
+            value[k][side] = match best_above[opponents] {
+                Some(overcall) => better_for(opponents, pass, overcall),
+                None => pass,
+            };
+        }
+        for (side, best) in best_above.iter_mut().enumerate() {
+            *best = Some(match *best {
+                Some(b) => better_for(side, b, value[k][side]),
+                None => value[k][side],
+            });
+        }
+    }
+
+    // NS open: either bid, or pass and let EW bid or pass it out
+    let ew_first = best_above[1].map_or(0, |v| v.min(0));
+    let score = best_above[0].map_or(ew_first, |v| v.max(ew_first));
+
+    let mut contracts = Vec::new();
+    if score != 0 {
+        for (k, scores) in final_score.iter().enumerate() {
+            let (level, denomination) = contract_at(k);
+            for (side, &final_ns) in scores.iter().enumerate() {
+                if final_ns != score || value[k][side] != score {
+                    continue;
+                }
+                let best =
+                    result.get_tricks(denomination, best_declarer(result, denomination, side));
+                let made = best as i32 >= level as i32 + 6;
+
+                // A sacrifice only belongs in the list if it outbids an opposing
+                // contract that would otherwise score better than par
+                let opponents = 1 - side;
+                if !made
+                    && !(0..k)
+                        .any(|j| better_for(opponents, final_score[j][opponents], score) != score)
+                {
+                    continue;
+                }
+
+                for declarer in side_positions(side) {
+                    let tricks = result.get_tricks(denomination, declarer);
+                    if tricks == best {
+                        contracts.push(ParContract {
+                            level,
+                            denomination,
+                            declarer,
+                            doubled: !made,
+                            tricks,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    ParResult { score, contracts }
+}
+
+/// Level and denomination of contract index k (0 = 1C, 34 = 7NT)
+fn contract_at(k: usize) -> (u8, Denomination) {
+    ((k / 5) as u8 + 1, Denomination::ALL[k % 5])
+}
+
+/// The two positions of a side (0 = NS, 1 = EW)
+fn side_positions(side: usize) -> [Position; 2] {
+    if side == 0 {
+        [Position::North, Position::South]
+    } else {
+        [Position::East, Position::West]
+    }
+}
+
+/// The declarer on a side who takes the most tricks in a denomination
+fn best_declarer(result: &DoubleDummyResult, denomination: Denomination, side: usize) -> Position {
+    let [first, second] = side_positions(side);
+    if result.get_tricks(denomination, second) > result.get_tricks(denomination, first) {
+        second
+    } else {
+        first
+    }
+}
+
+/// Pick the better of two NS-relative outcomes for a side
+fn better_for(side: usize, a: i32, b: i32) -> i32 {
+    if side == 0 {
+        a.max(b)
+    } else {
+        a.min(b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns true for North and South
+    fn is_ns(position: Position) -> bool {
+        matches!(position, Position::North | Position::South)
+    }
+
+    /// Build a table from per-denomination NS and EW trick counts (C, D, H, S, N)
+    fn table(ns: [u8; 5], ew: [u8; 5]) -> DoubleDummyResult {
+        let mut result = DoubleDummyResult::new();
+        for (i, denom) in Denomination::ALL.iter().enumerate() {
+            result.set_tricks(*denom, Position::North, ns[i]);
+            result.set_tricks(*denom, Position::South, ns[i]);
+            result.set_tricks(*denom, Position::East, ew[i]);
+            result.set_tricks(*denom, Position::West, ew[i]);
+        }
+        result
+    }
+
+    #[test]
+    fn test_contract_score_made() {
+        assert_eq!(
+            contract_score(3, Denomination::NoTrump, false, false, 9),
+            400
+        );
+        assert_eq!(
+            contract_score(4, Denomination::Spades, false, true, 10),
+            620
+        );
+        assert_eq!(
+            contract_score(1, Denomination::NoTrump, false, false, 8),
+            120
+        );
+        assert_eq!(contract_score(2, Denomination::Clubs, false, false, 8), 90);
+        assert_eq!(
+            contract_score(6, Denomination::Hearts, false, false, 12),
+            980
+        );
+        assert_eq!(
+            contract_score(7, Denomination::NoTrump, false, true, 13),
+            2220
+        );
+    }
+
+    #[test]
+    fn test_contract_score_doubled() {
+        // Doubled into game: 3D doubled making non-vulnerable
+        assert_eq!(
+            contract_score(3, Denomination::Diamonds, true, false, 9),
+            470
+        );
+        // Doubled partscore with an overtrick
+        assert_eq!(contract_score(2, Denomination::Clubs, true, true, 9), 380);
+    }
+
+    #[test]
+    fn test_contract_score_undertricks() {
+        assert_eq!(
+            contract_score(4, Denomination::Spades, false, false, 8),
+            -100
+        );
+        assert_eq!(
+            contract_score(4, Denomination::Spades, false, true, 8),
+            -200
+        );
+        assert_eq!(
+            contract_score(5, Denomination::Hearts, true, false, 9),
+            -300
+        );
+        assert_eq!(contract_score(4, Denomination::Spades, true, true, 7), -800);
+        assert_eq!(
+            contract_score(7, Denomination::Clubs, true, false, 6),
+            -1700
+        );
+    }
+
+    #[test]
+    fn test_par_cold_3nt() {
+        // NS make 3NT; EW have no cheap sacrifice
+        let result = table([8, 8, 7, 7, 9], [5, 5, 6, 6, 4]);
+        let par = result.par(Vulnerability::None);
+        assert_eq!(par.score, 400);
+        assert_eq!(par.contracts.len(), 2);
+        for contract in &par.contracts {
+            assert_eq!(contract.level, 3);
+            assert_eq!(contract.denomination, Denomination::NoTrump);
+            assert!(is_ns(contract.declarer));
+            assert!(!contract.doubled);
+        }
+    }
+
+    #[test]
+    fn test_par_ew_game() {
+        // EW make 4H vulnerable, NS have nothing better than a costly sacrifice
+        let result = table([6, 6, 3, 6, 5], [7, 7, 10, 7, 7]);
+        let par = result.par(Vulnerability::All);
+        assert_eq!(par.score, -620);
+        assert!(par
+            .contracts
+            .iter()
+            .all(|c| c.level == 4 && c.denomination == Denomination::Hearts && !is_ns(c.declarer)));
+    }
+
+    #[test]
+    fn test_par_sacrifice() {
+        // NS vulnerable make 4S; EW non-vulnerable sacrifice in 5H doubled, down two
+        let result = table([7, 7, 4, 10, 7], [6, 6, 9, 3, 6]);
+        let par = result.par(Vulnerability::NS);
+        assert_eq!(par.score, 300);
+        assert_eq!(par.contracts.len(), 2);
+        for contract in &par.contracts {
+            assert_eq!(contract.level, 5);
+            assert_eq!(contract.denomination, Denomination::Hearts);
+            assert!(contract.doubled);
+            assert_eq!(contract.tricks, 9);
+        }
+    }
+
+    #[test]
+    fn test_par_passed_out() {
+        let result = table([6; 5], [6; 5]);
+        let par = result.par(Vulnerability::None);
+        assert_eq!(par.score, 0);
+        assert!(par.contracts.is_empty());
+    }
+
+    #[test]
+    fn test_imp_diff_scale_boundaries() {
+        for (diff, imps) in [
+            (0, 0),
+            (10, 0),
+            (20, 1),
+            (40, 1),
+            (50, 2),
+            (370, 9),
+            (420, 9),
+            (430, 10),
+            (1490, 16),
+            (1500, 17),
+            (3990, 23),
+            (4000, 24),
+            (7600, 24),
+        ] {
+            assert_eq!(imp_diff(diff, 0), imps, "{} points", diff);
+            assert_eq!(imp_diff(0, diff), -imps, "-{} points", diff);
+        }
+
+        // 4H making (420) against 4H one down (-50) is a 470-point swing
+        assert_eq!(imp_diff(420, -50), 10);
+        // Game against partscore: 420 against 170
+        assert_eq!(imp_diff(170, 420), -6);
+    }
+
+    #[test]
+    fn test_dealer_imps_scale_boundaries() {
+        for (diff, imps) in [
+            (0, 0),
+            (9, 0),
+            (10, 1),
+            (39, 1),
+            (40, 2),
+            (80, 3),
+            (410, 10),
+            (420, 10),
+            (490, 11),
+            (1190, 16),
+            (1500, 17),
+            (4000, 24),
+        ] {
+            assert_eq!(dealer_imps(diff), imps, "{} points", diff);
+            assert_eq!(dealer_imps(-diff), -imps, "-{} points", diff);
+        }
+
+        // The two scales disagree: 420 is 10 IMPs here, 9 on the WBF scale
+        assert_eq!(dealer_imps(420), 10);
+        assert_eq!(imp_diff(420, 0), 9);
+    }
+
+    #[test]
+    fn test_matchpoints() {
+        let field = [420, 420, 450, 170, -50];
+        assert_eq!(matchpoints(450, &field[..2]), 2.0);
+        assert_eq!(matchpoints(420, &field), 3.0);
+        assert_eq!(matchpoints(-100, &field), 0.0);
+        assert_eq!(matchpoints(990, &field), 5.0);
+        assert_eq!(matchpoints(420, &[]), 0.0);
+    }
+}
@@ -1,51 +1,10 @@
-use dealer_core::{Card, Deal, Position, Suit};
-use dealer_dds::{Denomination, DoubleDummySolver};
-use dealer_parser::{BinaryOp, Expr, Function, Program, ShapePattern, Statement, UnaryOp};
+use dealer_core::{Card, Deal, Position, Side, Suit};
+use dealer_dds::{dealer_imps, Denomination, DoubleDummySolver};
+use dealer_parser::{
+    BinaryOp, Expr, Function, Program, ShapePattern, Statement, TemplatePart, UnaryOp,
+};
 use rustc_hash::FxHashMap;
-use std::cell::RefCell;
-
-/// IMP conversion table (from DealerV2_4)
-/// Maps score differences to IMP values
-/// Table[i] represents the minimum score difference for (i+1) IMPs
-const IMP_TABLE: [i32; 24] = [
-    10, 40, 80, 120, 160, 210, 260, 310, 360, 410, 490, 590, 740, 890, 1090, 1190, 1490, 1740,
-    1990, 2240, 2490, 2990, 3490, 3990,
-];
-
-/// Convert score difference to IMPs
-///
-/// Uses standard IMP table:
-/// - 0-9: 0 IMPs
-/// - 10-39: 1 IMP
-/// - 40-79: 2 IMPs
-/// - ... up to 3990+: 24 IMPs
-///
-/// Sign is preserved (negative score difference returns negative IMPs)
-fn score_to_imps(score_diff: i32) -> i32 {
-    let abs_diff = score_diff.abs();
-
-    if abs_diff == 0 {
-        return 0;
-    }
-
-    // Find the IMP value by searching the table
-    // Table[i] represents the minimum score for (i+1) IMPs
-    let mut imps = 0;
-    for (i, &threshold) in IMP_TABLE.iter().enumerate() {
-        if abs_diff >= threshold {
-            imps = (i + 1) as i32;
-        } else {
-            break;
-        }
-    }
-
-    // Preserve sign
-    if score_diff < 0 {
-        -imps
-    } else {
-        imps
-    }
-}
+use std::cell::{Cell, RefCell};
 
 /// Strain (denomination) for contract scoring
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -313,6 +272,34 @@ pub struct EvalContext<'a> {
     /// Keys are &str references to avoid String cloning on cache insert
     /// FxHashMap uses a faster (non-cryptographic) hash function
     cache: RefCell<FxHashMap<&'a str, i32>>,
+    /// Cache of per-hand metrics (hcp, suit lengths, controls, losers) for this deal,
+    /// one slot per (metric, position, optional suit); see metric_slot()
+    metrics: [Cell<i32>; METRIC_SLOTS],
+    /// Number of metrics computed from the hand (cache misses)
+    metric_misses: Cell<usize>,
+    /// Double-dummy trick counts already solved for this deal
+    dd_tricks: RefCell<FxHashMap<(Position, Denomination), u8>>,
+}
+
+/// Per-hand metrics kept in the metric cache
+#[derive(Debug, Clone, Copy)]
+enum Metric {
+    Hcp,
+    Controls,
+    Losers,
+    Length,
+}
+
+/// Slots in the metric cache: 4 metrics x 4 positions x (whole hand + 4 suits)
+const METRIC_SLOTS: usize = 4 * 4 * 5;
+
+/// Marks a metric cache slot that has not been computed for this deal
+const METRIC_UNSET: i32 = i32::MIN;
+
+/// Index of a metric in the cache; `None` is the whole hand
+fn metric_slot(metric: Metric, position: Position, suit: Option<Suit>) -> usize {
+    let suit_slot = suit.map_or(0, |suit| suit as usize + 1);
+    (metric as usize * 4 + position as usize) * 5 + suit_slot
 }
 
 /// Empty variables map for contexts without variables
@@ -326,6 +313,9 @@ impl<'a> EvalContext<'a> {
             deal,
             variables: &EMPTY_VARIABLES,
             cache: RefCell::new(FxHashMap::default()),
+            metrics: [const { Cell::new(METRIC_UNSET) }; METRIC_SLOTS],
+            metric_misses: Cell::new(0),
+            dd_tricks: RefCell::new(FxHashMap::default()),
         }
     }
 
@@ -335,7 +325,65 @@ impl<'a> EvalContext<'a> {
             deal,
             variables,
             cache: RefCell::new(FxHashMap::default()),
+            metrics: [const { Cell::new(METRIC_UNSET) }; METRIC_SLOTS],
+            metric_misses: Cell::new(0),
+            dd_tricks: RefCell::new(FxHashMap::default()),
+        }
+    }
+
+    /// Number of per-hand metrics computed so far; repeated lookups of the
+    /// same metric are served from the cache and do not add to this count
+    pub fn metric_misses(&self) -> usize {
+        self.metric_misses.get()
+    }
+
+    /// Number of distinct double-dummy solves run for this deal
+    pub fn dd_solves(&self) -> usize {
+        self.dd_tricks.borrow().len()
+    }
+
+    /// Forget every cached variable value, hand metric and double-dummy trick
+    /// count, so later lookups compute them again
+    ///
+    /// `metric_misses()` keeps counting across a reset.
+    pub fn reset_cache(&self) {
+        self.cache.borrow_mut().clear();
+        for slot in &self.metrics {
+            slot.set(METRIC_UNSET);
+        }
+        self.dd_tricks.borrow_mut().clear();
+    }
+
+    /// Double-dummy tricks for a declarer and denomination, solving at most once per deal
+    ///
+    /// `tricks()`, `ddscore()` and friends read the same cache, so a count
+    /// looked up here is not solved again when a script asks for it.
+    pub fn cached_tricks(&self, declarer: Position, denomination: Denomination) -> u8 {
+        let key = (declarer, denomination);
+        if let Some(&tricks) = self.dd_tricks.borrow().get(&key) {
+            return tricks;
         }
+        let tricks = DoubleDummySolver::new(self.deal.clone()).solve(denomination, declarer);
+        self.dd_tricks.borrow_mut().insert(key, tricks);
+        tricks
+    }
+
+    /// Look up a per-hand metric, computing and caching it on first use
+    fn cached_metric(
+        &self,
+        metric: Metric,
+        position: Position,
+        suit: Option<Suit>,
+        compute: impl FnOnce() -> i32,
+    ) -> i32 {
+        let slot = &self.metrics[metric_slot(metric, position, suit)];
+        if slot.get() != METRIC_UNSET {
+            return slot.get();
+        }
+        let value = compute();
+        self.metric_misses.set(self.metric_misses.get() + 1);
+        slot.set(value);
+        value
     }
 }
 
@@ -351,8 +399,13 @@ pub fn extract_variables(program: &Program) -> FxHashMap<String, &Expr> {
     variables
 }
 
-/// Extract the final constraint expression from a program (call once before the eval loop)
-pub fn extract_constraint(program: &Program) -> Option<&Expr> {
+/// Extract the constraint expression from a program (call once before the eval loop)
+///
+/// Explicit `condition` statements take precedence: if there are several they are
+/// combined with logical AND in source order. Without any `condition` statement,
+/// the last bare expression is used as the constraint.
+pub fn extract_constraint(program: &Program) -> Option<Expr> {
+    let mut condition: Option<Expr> = None;
     let mut final_expr = None;
     for statement in &program.statements {
         match statement {
@@ -360,12 +413,15 @@ pub fn extract_constraint(program: &Program) -> Option<&Expr> {
                 final_expr = Some(expr);
             }
             Statement::Condition(expr) => {
-                final_expr = Some(expr);
+                condition = Some(match condition {
+                    Some(prev) => Expr::binary(BinaryOp::And, prev, expr.clone()),
+                    None => expr.clone(),
+                });
             }
             _ => {}
         }
     }
-    final_expr
+    condition.or_else(|| final_expr.cloned())
 }
 
 /// Evaluate a constraint expression with pre-extracted variables against a deal
@@ -389,7 +445,8 @@ pub fn eval_with_context(
 ///
 /// Processes statements in order:
 /// - Assignments populate the variables HashMap
-/// - The last expression/condition is the constraint to evaluate
+/// - `condition` statements (ANDed together) form the constraint to evaluate,
+///   falling back to the last bare expression when there are none
 ///
 /// Note: Multiple bare expressions in a file - only the LAST one is used as the condition.
 /// Earlier expressions are evaluated for side effects but don't affect matching.
@@ -401,7 +458,20 @@ pub fn eval_program(program: &Program, deal: &Deal) -> Result<i32, EvalError> {
         EvalError::InvalidArgument("Program must end with a constraint expression".to_string())
     })?;
 
-    eval_with_context(constraint, &variables, deal)
+    eval_with_context(&constraint, &variables, deal)
+}
+
+/// Render a printf template against a deal, substituting each %{expr}
+/// with its evaluated value
+pub fn render_template(parts: &[TemplatePart], ctx: &EvalContext) -> Result<String, EvalError> {
+    let mut out = String::new();
+    for part in parts {
+        match part {
+            TemplatePart::Text(text) => out.push_str(text),
+            TemplatePart::Expr(expr) => out.push_str(&eval(expr, ctx)?.to_string()),
+        }
+    }
+    Ok(out)
 }
 
 /// Evaluate an expression against a deal
@@ -557,11 +627,42 @@ pub fn eval(expr: &Expr, ctx: &EvalContext) -> Result<i32, EvalError> {
                 "Suit can only be used as argument to functions like losers()".to_string(),
             ))
         }
+
+        Expr::NoTrump => Err(EvalError::InvalidArgument(
+            "notrump can only be used as argument to tricks()".to_string(),
+        )),
+
+        Expr::Side(_side) => Err(EvalError::InvalidArgument(
+            "ns/ew can only be used as the first argument of hcp(), controls(), losers(), fit() and suit lengths"
+                .to_string(),
+        )),
     }
 }
 
 /// Evaluate a function call
 fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Result<i32, EvalError> {
+    // hcp(ns) and the like: the same function summed over both hands of the side
+    if let Some(Expr::Side(side)) = args.first() {
+        if matches!(
+            function,
+            Function::Hcp
+                | Function::Controls
+                | Function::Losers
+                | Function::Spades
+                | Function::Hearts
+                | Function::Diamonds
+                | Function::Clubs
+        ) {
+            let mut hand_args = args.to_vec();
+            let mut total = 0;
+            for position in side.positions() {
+                hand_args[0] = Expr::Position(position);
+                total += eval_function(function, &hand_args, ctx)?;
+            }
+            return Ok(total);
+        }
+    }
+
     match function {
         Function::Hcp => {
             // hcp(position) - total HCP for a hand
@@ -573,17 +674,31 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
                     got: args.len(),
                 });
             }
-            let position = eval_position_arg(&args[0], ctx)?;
+            let position = eval_position_arg(&args[0])?;
             let hand = ctx.deal.hand(position);
 
             if args.len() == 2 {
                 // HCP in a specific suit
                 let suit = eval_suit_arg(&args[1])?;
-                let hcp: u8 = hand.cards_in_suit(suit).iter().map(|c| c.hcp()).sum();
-                Ok(hcp as i32)
+                Ok(ctx.cached_metric(Metric::Hcp, position, Some(suit), || {
+                    hand.hcp_in_suit(suit) as i32
+                }))
             } else {
-                Ok(hand.hcp() as i32)
+                Ok(ctx.cached_metric(Metric::Hcp, position, None, || hand.hcp() as i32))
+            }
+        }
+
+        Function::HcpAdjusted => {
+            if args.len() != 1 {
+                return Err(EvalError::InvalidArgumentCount {
+                    function: "ahcp".to_string(),
+                    expected: 1,
+                    got: args.len(),
+                });
             }
+            let position = eval_position_arg(&args[0])?;
+            let hand = ctx.deal.hand(position);
+            Ok(hand.hcp_adjusted())
         }
 
         Function::Hearts => {
@@ -594,9 +709,13 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
                     got: args.len(),
                 });
             }
-            let position = eval_position_arg(&args[0], ctx)?;
+            let position = eval_position_arg(&args[0])?;
             let hand = ctx.deal.hand(position);
-            Ok(hand.suit_length(dealer_core::Suit::Hearts) as i32)
+            Ok(
+                ctx.cached_metric(Metric::Length, position, Some(Suit::Hearts), || {
+                    hand.suit_length(Suit::Hearts) as i32
+                }),
+            )
         }
 
         Function::Spades => {
@@ -607,9 +726,13 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
                     got: args.len(),
                 });
             }
-            let position = eval_position_arg(&args[0], ctx)?;
+            let position = eval_position_arg(&args[0])?;
             let hand = ctx.deal.hand(position);
-            Ok(hand.suit_length(dealer_core::Suit::Spades) as i32)
+            Ok(
+                ctx.cached_metric(Metric::Length, position, Some(Suit::Spades), || {
+                    hand.suit_length(Suit::Spades) as i32
+                }),
+            )
         }
 
         Function::Diamonds => {
@@ -620,9 +743,13 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
                     got: args.len(),
                 });
             }
-            let position = eval_position_arg(&args[0], ctx)?;
+            let position = eval_position_arg(&args[0])?;
             let hand = ctx.deal.hand(position);
-            Ok(hand.suit_length(dealer_core::Suit::Diamonds) as i32)
+            Ok(
+                ctx.cached_metric(Metric::Length, position, Some(Suit::Diamonds), || {
+                    hand.suit_length(Suit::Diamonds) as i32
+                }),
+            )
         }
 
         Function::Clubs => {
@@ -633,9 +760,13 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
                     got: args.len(),
                 });
             }
-            let position = eval_position_arg(&args[0], ctx)?;
+            let position = eval_position_arg(&args[0])?;
             let hand = ctx.deal.hand(position);
-            Ok(hand.suit_length(dealer_core::Suit::Clubs) as i32)
+            Ok(
+                ctx.cached_metric(Metric::Length, position, Some(Suit::Clubs), || {
+                    hand.suit_length(Suit::Clubs) as i32
+                }),
+            )
         }
 
         Function::Controls => {
@@ -648,24 +779,19 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
                     got: args.len(),
                 });
             }
-            let position = eval_position_arg(&args[0], ctx)?;
+            let position = eval_position_arg(&args[0])?;
             let hand = ctx.deal.hand(position);
 
             if args.len() == 2 {
                 // Controls in a specific suit (A=2, K=1)
                 let suit = eval_suit_arg(&args[1])?;
-                let controls: u8 = hand
-                    .cards_in_suit(suit)
-                    .iter()
-                    .map(|c| match c.rank {
-                        dealer_core::Rank::Ace => 2,
-                        dealer_core::Rank::King => 1,
-                        _ => 0,
-                    })
-                    .sum();
-                Ok(controls as i32)
+                Ok(
+                    ctx.cached_metric(Metric::Controls, position, Some(suit), || {
+                        hand.controls_in_suit(suit) as i32
+                    }),
+                )
             } else {
-                Ok(hand.controls() as i32)
+                Ok(ctx.cached_metric(Metric::Controls, position, None, || hand.controls() as i32))
             }
         }
 
@@ -677,7 +803,7 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
                     got: args.len(),
                 });
             }
-            let position = eval_position_arg(&args[0], ctx)?;
+            let position = eval_position_arg(&args[0])?;
             let pattern = match &args[1] {
                 Expr::ShapePattern(p) => p,
                 _ => {
@@ -701,16 +827,54 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
                 });
             }
 
-            let position = eval_position_arg(&args[0], ctx)?;
+            let position = eval_position_arg(&args[0])?;
             let hand = ctx.deal.hand(position);
 
             if args.len() == 1 {
                 // Total losers in hand
-                Ok(hand.losers() as i32)
+                Ok(ctx.cached_metric(Metric::Losers, position, None, || hand.losers() as i32))
             } else {
                 // Losers in specific suit
                 let suit = eval_suit_arg(&args[1])?;
-                Ok(hand.losers_in_suit(suit) as i32)
+                Ok(ctx.cached_metric(Metric::Losers, position, Some(suit), || {
+                    hand.losers_in_suit(suit) as i32
+                }))
+            }
+        }
+
+        Function::CoverLosers => {
+            if args.len() != 2 {
+                return Err(EvalError::InvalidArgumentCount {
+                    function: "coverlosers".to_string(),
+                    expected: 2,
+                    got: args.len(),
+                });
+            }
+
+            let position = eval_position_arg(&args[0])?;
+            let partner = eval_position_arg(&args[1])?;
+            let hand = ctx.deal.hand(position);
+
+            Ok(hand.losers_covered(ctx.deal.hand(partner)) as i32)
+        }
+
+        Function::NewLosers => {
+            if args.is_empty() || args.len() > 2 {
+                return Err(EvalError::InvalidArgumentCount {
+                    function: "nltc".to_string(),
+                    expected: 1, // or 2 with suit
+                    got: args.len(),
+                });
+            }
+
+            let position = eval_position_arg(&args[0])?;
+            let hand = ctx.deal.hand(position);
+
+            if args.len() == 1 {
+                Ok(hand.new_losers() as i32)
+            } else {
+                let suit = eval_suit_arg(&args[1])?;
+                Ok(hand.new_losers_in_suit(suit) as i32)
             }
         }
 
@@ -723,13 +887,32 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
                 });
             }
 
-            let position = eval_position_arg(&args[0], ctx)?;
+            let position = eval_position_arg(&args[0])?;
             let card = eval_card_arg(&args[1])?;
             let hand = ctx.deal.hand(position);
 
             Ok(if hand.has_card(card) { 1 } else { 0 })
         }
 
+        Function::HasAnyCard => {
+            if args.len() < 2 {
+                return Err(EvalError::InvalidArgumentCount {
+                    function: "hasanycard".to_string(),
+                    expected: 2,
+                    got: args.len(),
+                });
+            }
+
+            let position = eval_position_arg(&args[0])?;
+            let hand = ctx.deal.hand(position);
+            for arg in &args[1..] {
+                if hand.has_card(eval_card_arg(arg)?) {
+                    return Ok(1);
+                }
+            }
+            Ok(0)
+        }
+
         // Alternative point counts (pt0-pt9 / readable synonyms)
         Function::Tens => {
             if args.is_empty() || args.len() > 2 {
@@ -740,7 +923,7 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
                 });
             }
 
-            let position = eval_position_arg(&args[0], ctx)?;
+            let position = eval_position_arg(&args[0])?;
             let hand = ctx.deal.hand(position);
 
             if args.len() == 1 {
@@ -760,7 +943,7 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
                 });
             }
 
-            let position = eval_position_arg(&args[0], ctx)?;
+            let position = eval_position_arg(&args[0])?;
             let hand = ctx.deal.hand(position);
 
             if args.len() == 1 {
@@ -780,7 +963,7 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
                 });
             }
 
-            let position = eval_position_arg(&args[0], ctx)?;
+            let position = eval_position_arg(&args[0])?;
             let hand = ctx.deal.hand(position);
 
             if args.len() == 1 {
@@ -800,7 +983,7 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
                 });
             }
 
-            let position = eval_position_arg(&args[0], ctx)?;
+            let position = eval_position_arg(&args[0])?;
             let hand = ctx.deal.hand(position);
 
             if args.len() == 1 {
@@ -820,7 +1003,7 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
                 });
             }
 
-            let position = eval_position_arg(&args[0], ctx)?;
+            let position = eval_position_arg(&args[0])?;
             let hand = ctx.deal.hand(position);
 
             if args.len() == 1 {
@@ -840,7 +1023,7 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
                 });
             }
 
-            let position = eval_position_arg(&args[0], ctx)?;
+            let position = eval_position_arg(&args[0])?;
             let hand = ctx.deal.hand(position);
 
             if args.len() == 1 {
@@ -860,7 +1043,7 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
                 });
             }
 
-            let position = eval_position_arg(&args[0], ctx)?;
+            let position = eval_position_arg(&args[0])?;
             let hand = ctx.deal.hand(position);
 
             if args.len() == 1 {
@@ -880,7 +1063,7 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
                 });
             }
 
-            let position = eval_position_arg(&args[0], ctx)?;
+            let position = eval_position_arg(&args[0])?;
             let hand = ctx.deal.hand(position);
 
             if args.len() == 1 {
@@ -900,7 +1083,7 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
                 });
             }
 
-            let position = eval_position_arg(&args[0], ctx)?;
+            let position = eval_position_arg(&args[0])?;
             let hand = ctx.deal.hand(position);
 
             if args.len() == 1 {
@@ -911,6 +1094,43 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
             }
         }
 
+        Function::Top => {
+            if args.len() != 3 {
+                return Err(EvalError::InvalidArgumentCount {
+                    function: "top".to_string(),
+                    expected: 3,
+                    got: args.len(),
+                });
+            }
+
+            let position = eval_position_arg(&args[0])?;
+            let suit = eval_suit_arg(&args[1])?;
+            let n = eval(&args[2], ctx)?;
+            if !(0..=13).contains(&n) {
+                return Err(EvalError::InvalidArgument(format!(
+                    "top() count must be between 0 and 13, got {}",
+                    n
+                )));
+            }
+
+            Ok(ctx.deal.hand(position).top_n_in_suit(suit, n as u8) as i32)
+        }
+
+        Function::Intermediates => {
+            if args.len() != 2 {
+                return Err(EvalError::InvalidArgumentCount {
+                    function: "intermediates".to_string(),
+                    expected: 2,
+                    got: args.len(),
+                });
+            }
+
+            let position = eval_position_arg(&args[0])?;
+            let suit = eval_suit_arg(&args[1])?;
+
+            Ok(ctx.deal.hand(position).intermediates_in_suit(suit) as i32)
+        }
+
         Function::C13 => {
             if args.is_empty() || args.len() > 2 {
                 return Err(EvalError::InvalidArgumentCount {
@@ -920,7 +1140,7 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
                 });
             }
 
-            let position = eval_position_arg(&args[0], ctx)?;
+            let position = eval_position_arg(&args[0])?;
             let hand = ctx.deal.hand(position);
 
             if args.len() == 1 {
@@ -931,6 +1151,27 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
             }
         }
 
+        Function::QuickTricks => {
+            if args.is_empty() || args.len() > 2 {
+                return Err(EvalError::InvalidArgumentCount {
+                    function: "qtricks".to_string(),
+                    // qtricks(position) or qtricks(position, suit)
+                    expected: if args.is_empty() { 1 } else { 2 },
+                    got: args.len(),
+                });
+            }
+
+            let position = eval_position_arg(&args[0])?;
+            let hand = ctx.deal.hand(position);
+
+            if args.len() == 1 {
+                Ok(hand.quick_tricks() as i32)
+            } else {
+                let suit = eval_suit_arg(&args[1])?;
+                Ok(hand.quick_tricks_in_suit(suit) as i32)
+            }
+        }
+
         Function::Quality => {
             if args.len() != 2 {
                 return Err(EvalError::InvalidArgumentCount {
@@ -940,7 +1181,7 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
                 });
             }
 
-            let position = eval_position_arg(&args[0], ctx)?;
+            let position = eval_position_arg(&args[0])?;
             let suit = eval_suit_arg(&args[1])?;
             let hand = ctx.deal.hand(position);
 
@@ -956,12 +1197,85 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
                 });
             }
 
-            let position = eval_position_arg(&args[0], ctx)?;
+            let position = eval_position_arg(&args[0])?;
             let hand = ctx.deal.hand(position);
 
             Ok(hand.cccc())
         }
 
+        Function::Zar => {
+            if args.len() != 1 {
+                return Err(EvalError::InvalidArgumentCount {
+                    function: "zar".to_string(),
+                    expected: 1,
+                    got: args.len(),
+                });
+            }
+
+            let position = eval_position_arg(&args[0])?;
+            let hand = ctx.deal.hand(position);
+
+            Ok(hand.zar_points() as i32)
+        }
+
+        Function::ShortnessPoints | Function::LengthPoints => {
+            if args.len() != 1 {
+                return Err(EvalError::InvalidArgumentCount {
+                    function: function.name().to_string(),
+                    expected: 1,
+                    got: args.len(),
+                });
+            }
+
+            let position = eval_position_arg(&args[0])?;
+            let hand = ctx.deal.hand(position);
+
+            Ok(match function {
+                Function::ShortnessPoints => hand.shortness_points(),
+                _ => hand.length_points(),
+            } as i32)
+        }
+
+        Function::Longest | Function::Shortest => {
+            if args.len() != 1 {
+                return Err(EvalError::InvalidArgumentCount {
+                    function: function.name().to_string(),
+                    expected: 1,
+                    got: args.len(),
+                });
+            }
+
+            let position = eval_position_arg(&args[0])?;
+            let hand = ctx.deal.hand(position);
+            let (_, length) = if *function == Function::Longest {
+                hand.longest_suit()
+            } else {
+                hand.shortest_suit()
+            };
+
+            Ok(length as i32)
+        }
+
+        Function::Balanced | Function::SemiBalanced => {
+            if args.len() != 1 {
+                return Err(EvalError::InvalidArgumentCount {
+                    function: function.name().to_string(),
+                    expected: 1,
+                    got: args.len(),
+                });
+            }
+
+            let position = eval_position_arg(&args[0])?;
+            let hand = ctx.deal.hand(position);
+            let result = if *function == Function::Balanced {
+                hand.is_balanced()
+            } else {
+                hand.is_semi_balanced()
+            };
+
+            Ok(if result { 1 } else { 0 })
+        }
+
         Function::Tricks => {
             // tricks(position, denomination)
             // position: north/south/east/west
@@ -974,48 +1288,12 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
                 });
             }
 
-            let position = eval_position_arg(&args[0], ctx)?;
-
-            // Parse denomination - can be numeric (0-4) or suit keyword
-            let denomination = match &args[1] {
-                Expr::Suit(suit) => Denomination::from_suit(*suit),
-                Expr::Literal(n) => match n {
-                    0 => Denomination::Clubs,
-                    1 => Denomination::Diamonds,
-                    2 => Denomination::Hearts,
-                    3 => Denomination::Spades,
-                    4 => Denomination::NoTrump,
-                    _ => {
-                        return Err(EvalError::InvalidArgument(format!(
-                            "Invalid denomination: {} (must be 0=C, 1=D, 2=H, 3=S, 4=NT)",
-                            n
-                        )));
-                    }
-                },
-                _ => {
-                    // Try to evaluate as an expression
-                    let n = eval(&args[1], ctx)?;
-                    match n {
-                        0 => Denomination::Clubs,
-                        1 => Denomination::Diamonds,
-                        2 => Denomination::Hearts,
-                        3 => Denomination::Spades,
-                        4 => Denomination::NoTrump,
-                        _ => {
-                            return Err(EvalError::InvalidArgument(format!(
-                                "Invalid denomination: {} (must be 0=C, 1=D, 2=H, 3=S, 4=NT)",
-                                n
-                            )));
-                        }
-                    }
-                }
-            };
+            let position = eval_position_arg(&args[0])?;
 
-            // Create solver and solve
-            let solver = DoubleDummySolver::new(ctx.deal.clone());
-            let tricks = solver.solve(denomination, position);
+            let denomination = eval_denomination_arg(&args[1], ctx)?;
 
-            Ok(tricks as i32)
+            // Solving is expensive, so each (declarer, denomination) is solved once per deal
+            Ok(ctx.cached_tricks(position, denomination) as i32)
         }
 
         Function::Score => {
@@ -1100,6 +1378,38 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
             Ok(calculate_score(vulnerable, &contract, tricks as u8))
         }
 
+        Function::DdScore => {
+            // ddscore(declarer, denomination, level, vulnerable)
+            // Score of the undoubled contract for the double-dummy trick count
+            if args.len() != 4 {
+                return Err(EvalError::InvalidArgumentCount {
+                    function: "ddscore".to_string(),
+                    expected: 4,
+                    got: args.len(),
+                });
+            }
+
+            let position = eval_position_arg(&args[0])?;
+            let denomination = eval_denomination_arg(&args[1], ctx)?;
+            let level = eval(&args[2], ctx)?;
+            if !(1..=7).contains(&level) {
+                return Err(EvalError::InvalidArgument(format!(
+                    "Invalid contract level: {} (must be 1-7)",
+                    level
+                )));
+            }
+            let vulnerable = eval(&args[3], ctx)? != 0;
+
+            let tricks = ctx.cached_tricks(position, denomination);
+            Ok(dealer_dds::contract_score(
+                level as u8,
+                denomination,
+                false,
+                vulnerable,
+                tricks,
+            ))
+        }
+
         Function::Imps => {
             if args.len() != 1 {
                 return Err(EvalError::InvalidArgumentCount {
@@ -1112,16 +1422,74 @@ fn eval_function(function: &Function, args: &[Expr], ctx: &EvalContext) -> Resul
             // Evaluate the score difference expression
             let score_diff = eval(&args[0], ctx)?;
 
-            // Convert to IMPs using the standard table
-            Ok(score_to_imps(score_diff))
+            // DealerV2_4's table, not the WBF scale of dealer_dds::imp_diff
+            Ok(dealer_imps(score_diff))
+        }
+
+        Function::Between => {
+            if args.len() != 3 {
+                return Err(EvalError::InvalidArgumentCount {
+                    function: "between".to_string(),
+                    expected: 3,
+                    got: args.len(),
+                });
+            }
+
+            // All three arguments are full expressions, not positions
+            let value = eval(&args[0], ctx)?;
+            let low = eval(&args[1], ctx)?;
+            let high = eval(&args[2], ctx)?;
+
+            Ok(if low <= value && value <= high { 1 } else { 0 })
+        }
+
+        Function::Fit => {
+            if args.len() != 2 {
+                return Err(EvalError::InvalidArgumentCount {
+                    function: "fit".to_string(),
+                    expected: 2,
+                    got: args.len(),
+                });
+            }
+            // fit(north, spades) is the same as fit(ns, spades)
+            let side = match &args[0] {
+                Expr::Side(side) => *side,
+                arg => match eval_position_arg(arg)? {
+                    Position::North | Position::South => Side::NS,
+                    Position::East | Position::West => Side::EW,
+                },
+            };
+            let suit = eval_suit_arg(&args[1])?;
+            Ok(ctx.deal.combined_suit_length(side, suit) as i32)
+        }
+
+        Function::Partner => {
+            // partner() yields a position, not a number; eval_position_arg resolves it
+            Err(EvalError::InvalidArgument(
+                "partner() can only be used as a position argument, e.g. hcp(partner(north))"
+                    .to_string(),
+            ))
         }
     }
 }
 
 /// Evaluate an argument that should be a position
-fn eval_position_arg(arg: &Expr, _ctx: &EvalContext) -> Result<Position, EvalError> {
+fn eval_position_arg(arg: &Expr) -> Result<Position, EvalError> {
     match arg {
         Expr::Position(pos) => Ok(*pos),
+        Expr::FunctionCall {
+            func: Function::Partner,
+            args,
+        } => {
+            if args.len() != 1 {
+                return Err(EvalError::InvalidArgumentCount {
+                    function: "partner".to_string(),
+                    expected: 1,
+                    got: args.len(),
+                });
+            }
+            Ok(eval_position_arg(&args[0])?.partner())
+        }
         _ => Err(EvalError::InvalidArgument(
             "Expected position (north, south, east, west)".to_string(),
         )),
@@ -1138,6 +1506,27 @@ fn eval_suit_arg(arg: &Expr) -> Result<Suit, EvalError> {
     }
 }
 
+/// Evaluate an argument that should be a denomination: a suit keyword,
+/// `notrump`, or a number 0=C, 1=D, 2=H, 3=S, 4=NT
+fn eval_denomination_arg(arg: &Expr, ctx: &EvalContext) -> Result<Denomination, EvalError> {
+    let n = match arg {
+        Expr::Suit(suit) => return Ok(Denomination::from_suit(*suit)),
+        Expr::NoTrump => return Ok(Denomination::NoTrump),
+        _ => eval(arg, ctx)?,
+    };
+    match n {
+        0 => Ok(Denomination::Clubs),
+        1 => Ok(Denomination::Diamonds),
+        2 => Ok(Denomination::Hearts),
+        3 => Ok(Denomination::Spades),
+        4 => Ok(Denomination::NoTrump),
+        _ => Err(EvalError::InvalidArgument(format!(
+            "Invalid denomination: {} (must be 0=C, 1=D, 2=H, 3=S, 4=NT)",
+            n
+        ))),
+    }
+}
+
 /// Evaluate an argument that should be a card
 fn eval_card_arg(arg: &Expr) -> Result<Card, EvalError> {
     match arg {
@@ -1239,6 +1628,24 @@ mod tests {
         assert_eq!(eval(&expr, &ctx).unwrap(), expected_hcp);
     }
 
+    #[test]
+    fn test_eval_hcp_in_suit() {
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let ctx = EvalContext::new(&deal);
+
+        // Seed 1 north: AKQT3.J6.KJ42.95
+        let ast = parse("hcp(north, spades)").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 9);
+
+        // Per-suit HCP must add up to the whole-hand total
+        let ast = parse(
+            "hcp(north, spades) + hcp(north, hearts) + hcp(north, diamonds) + hcp(north, clubs) == hcp(north)",
+        )
+        .unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 1);
+    }
+
     #[test]
     fn test_eval_suit_length_functions() {
         let mut gen = DealGenerator::new(1);
@@ -1398,17 +1805,43 @@ mod tests {
     }
 
     #[test]
-    fn test_shape_combination() {
-        let mut gen = DealGenerator::new(42);
-        let mut found = false;
+    fn test_shape_range() {
+        let mut gen = DealGenerator::new(1);
+        let any = (None, None);
 
-        // Find a hand that's balanced (4333 or 4432 or 5332)
-        for _ in 0..1000 {
+        for _ in 0..100 {
             let deal = gen.generate();
+            let ctx = EvalContext::new(&deal);
             let north = deal.hand(Position::North);
-            let dist = north.distribution();
 
-            if matches!(dist, [4, 3, 3, 3] | [4, 4, 3, 2] | [5, 3, 3, 2]) {
+            let ast = parse("shape(north, 5+ 4+ x x)").unwrap();
+            let expected = north.matches_range_shape(&[(Some(5), None), (Some(4), None), any, any]);
+            assert_eq!(eval(&ast, &ctx).unwrap(), expected as i32);
+
+            let ast = parse("shape(north, 2-4 2-4 2-4 2-4)").unwrap();
+            let expected = north.matches_range_shape(&[(Some(2), Some(4)); 4]);
+            assert_eq!(eval(&ast, &ctx).unwrap(), expected as i32);
+        }
+
+        // Outside shape() the same tokens are still arithmetic
+        let deal = DealGenerator::new(1).generate();
+        let ctx = EvalContext::new(&deal);
+        let ast = parse("1+ 2+ 3+ 4").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 10);
+    }
+
+    #[test]
+    fn test_shape_combination() {
+        let mut gen = DealGenerator::new(42);
+        let mut found = false;
+
+        // Find a hand that's balanced (4333 or 4432 or 5332)
+        for _ in 0..1000 {
+            let deal = gen.generate();
+            let north = deal.hand(Position::North);
+            let dist = north.distribution();
+
+            if matches!(dist, [4, 3, 3, 3] | [4, 4, 3, 2] | [5, 3, 3, 2]) {
                 let ctx = EvalContext::new(&deal);
                 let ast = parse("shape(north, any 4333 + any 4432 + any 5332)").unwrap();
                 let result = eval(&ast, &ctx).unwrap();
@@ -1509,6 +1942,207 @@ mod tests {
         assert_eq!(result, 1);
     }
 
+    #[test]
+    fn test_longest_shortest() {
+        // Seed 1 north: AKQT3.J6.KJ42.95 (5-2-4-2)
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let ctx = EvalContext::new(&deal);
+
+        assert_eq!(eval(&parse("longest(north)").unwrap(), &ctx).unwrap(), 5);
+        assert_eq!(eval(&parse("shortest(north)").unwrap(), &ctx).unwrap(), 2);
+        assert!(eval(&parse("longest(north, spades)").unwrap(), &ctx).is_err());
+    }
+
+    #[test]
+    fn test_balanced_semibalanced() {
+        // Seed 1: north AKQT3.J6.KJ42.95 (5-4-2-2), east 652.AK42.AQ87.T4 (4-4-3-2),
+        // south J74.QT95.T.AK863 (5-4-3-1)
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let ctx = EvalContext::new(&deal);
+        let check = |input: &str| eval(&parse(input).unwrap(), &ctx).unwrap();
+
+        assert_eq!(check("balanced(north)"), 0);
+        assert_eq!(check("semibalanced(north)"), 1);
+        assert_eq!(check("balanced(east)"), 1);
+        assert_eq!(check("semibalanced(east)"), 1);
+        assert_eq!(check("balanced(south)"), 0);
+        assert_eq!(check("semibalanced(south)"), 0);
+        assert!(eval(&parse("balanced(north, spades)").unwrap(), &ctx).is_err());
+    }
+
+    #[test]
+    fn test_top_n() {
+        // Seed 1 north: AKQT3.J6.KJ42.95
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let ctx = EvalContext::new(&deal);
+        let check = |input: &str| eval(&parse(input).unwrap(), &ctx).unwrap();
+
+        assert_eq!(check("top(north, spades, 3)"), check("top3(north, spades)"));
+        assert_eq!(
+            check("top(north, diamonds, 5)"),
+            check("top5(north, diamonds)")
+        );
+        assert_eq!(check("top(north, spades, 5)"), 4);
+        assert_eq!(check("top(north, diamonds, 1 + 3)"), 2);
+        assert!(eval(&parse("top(north, spades, 14)").unwrap(), &ctx).is_err());
+        assert!(eval(&parse("top(north, spades)").unwrap(), &ctx).is_err());
+    }
+
+    #[test]
+    fn test_coverlosers() {
+        // Seed 1: north AKQT3.J6.KJ42.95 has 6 losers; south's club AK covers two
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let ctx = EvalContext::new(&deal);
+        let check = |input: &str| eval(&parse(input).unwrap(), &ctx).unwrap();
+
+        assert_eq!(check("losers(north)"), 6);
+        assert_eq!(check("coverlosers(north, south)"), 4);
+        assert_eq!(check("coverlosers(north, partner(north))"), 4);
+        assert!(eval(&parse("coverlosers(north)").unwrap(), &ctx).is_err());
+    }
+
+    #[test]
+    fn test_intermediates() {
+        // Seed 1: north AKQT3.J6.KJ42.95, south J74.QT95.T.AK863
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let ctx = EvalContext::new(&deal);
+        let check = |input: &str| eval(&parse(input).unwrap(), &ctx).unwrap();
+
+        assert_eq!(check("intermediates(north, spades)"), 1);
+        assert_eq!(check("intermediates(north, clubs)"), 1);
+        assert_eq!(check("intermediates(north, hearts)"), 0);
+        assert_eq!(check("intermediates(south, hearts)"), 2);
+        assert!(eval(&parse("intermediates(north)").unwrap(), &ctx).is_err());
+    }
+
+    #[test]
+    fn test_side_arguments() {
+        // Seed 1: north AKQT3.J6.KJ42.95, south J74.QT95.T.AK863
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let ctx = EvalContext::new(&deal);
+        let check = |input: &str| eval(&parse(input).unwrap(), &ctx).unwrap();
+
+        assert_eq!(check("hcp(ns)"), 24);
+        assert_eq!(check("hcp(ns) == hcp(north) + hcp(south)"), 1);
+        assert_eq!(check("hcp(EW)"), 40 - 24);
+        assert_eq!(check("hcp(ns, spades)"), 10);
+        assert_eq!(check("spades(ns)"), 8);
+        assert_eq!(check("spade(ns)"), 8);
+        assert_eq!(check("clubs(ns)"), check("clubs(north) + clubs(south)"));
+        assert_eq!(
+            check("controls(ns)"),
+            check("controls(north) + controls(south)")
+        );
+        assert_eq!(check("losers(ew)"), check("losers(east) + losers(west)"));
+        assert_eq!(
+            check("losers(ns, hearts)"),
+            check("losers(north, hearts) + losers(south, hearts)")
+        );
+
+        // Outside those functions ns is still an ordinary variable name
+        assert!(matches!(parse("ns + 1"), Ok(Expr::BinaryOp { .. })));
+    }
+
+    #[test]
+    fn test_fit() {
+        // Seed 1: north AKQT3.J6.KJ42.95, east 652.AK42.AQ87.T4,
+        //         south J74.QT95.T.AK863, west 98.873.9653.QJ72
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let ctx = EvalContext::new(&deal);
+        let check = |input: &str| eval(&parse(input).unwrap(), &ctx).unwrap();
+
+        // 5-3 spade fit for North-South
+        assert_eq!(check("fit(ns, spades)"), 8);
+        // 4-4 diamond fit for East-West
+        assert_eq!(check("fit(ew, diamonds)"), 8);
+        assert_eq!(check("fit(ns, diamonds)"), 5);
+        // A seat stands for its partnership
+        assert_eq!(check("fit(south, spades)"), 8);
+        assert_eq!(
+            check("fit(partner(east), hearts)"),
+            check("fit(ew, hearts)")
+        );
+        assert_eq!(check("fit(ns, spades) >= 8 && fit(ew, hearts) < 8"), 1);
+
+        assert!(eval(&parse("fit(ns)").unwrap(), &ctx).is_err());
+        assert!(eval(&parse("fit(ns, 3)").unwrap(), &ctx).is_err());
+    }
+
+    #[test]
+    fn test_render_template() {
+        use dealer_parser::{parse_program, ActionType};
+
+        // Seed 1: north AKQT3.J6.KJ42.95, south J74.QT95.T.AK863
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let ctx = EvalContext::new(&deal);
+
+        let program = parse_program("action printf \"N:%{hcp(north)} S:%{hcp(south)}\"").unwrap();
+        let parts = match &program.statements[0] {
+            Statement::Action {
+                format: Some(ActionType::PrintF(parts)),
+                ..
+            } => parts,
+            _ => panic!("Expected printf action"),
+        };
+        assert_eq!(render_template(parts, &ctx).unwrap(), "N:14 S:10");
+    }
+
+    #[test]
+    fn test_partner_position() {
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let ctx = EvalContext::new(&deal);
+
+        let south = eval(&parse("hcp(south)").unwrap(), &ctx).unwrap();
+        let ast = parse("hcp(partner(north))").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), south);
+
+        // Nests, and works for other position arguments
+        let ast = parse("hcp(partner(partner(north))) == hcp(north)").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 1);
+        let ast = parse("spades(partner(e)) == spades(west)").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 1);
+
+        // Not a number on its own
+        let ast = parse("partner(north)").unwrap();
+        assert!(eval(&ast, &ctx).is_err());
+    }
+
+    #[test]
+    fn test_hasanycard() {
+        // Seed 1 north: AKQT3.J6.KJ42.95
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let ctx = EvalContext::new(&deal);
+
+        // Matches on the second card
+        let ast = parse("hasanycard(north, AH, KD)").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 1);
+
+        // Single card behaves like hascard
+        let ast = parse("hasanycard(north, AS)").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 1);
+
+        // None held
+        let ast = parse("hasanycard(north, AH, KH, QH)").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 0);
+
+        // No cards given
+        let ast = parse("hasanycard(north)").unwrap();
+        assert!(matches!(
+            eval(&ast, &ctx),
+            Err(EvalError::InvalidArgumentCount { got: 1, .. })
+        ));
+    }
+
     #[test]
     fn test_losers_various_holdings() {
         use dealer_core::{Card, Hand, Rank, Suit};
@@ -1702,6 +2336,66 @@ mod tests {
         assert_eq!(result, north.c13() as i32);
     }
 
+    #[test]
+    fn test_eval_qtricks() {
+        // Seed 1 north: AKQT3.J6.KJ42.95 -> AK (2) + Kx (0.5) = 2.5 quick tricks
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let ctx = EvalContext::new(&deal);
+
+        let ast = parse("qtricks(north)").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 5);
+
+        let ast = parse("qtricks(north) >= 4").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 1);
+
+        let ast = parse("qtricks(north, diamonds)").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 1);
+
+        let ast = parse("qtricks(north, diamonds, hearts)").unwrap();
+        assert_eq!(
+            eval(&ast, &ctx).unwrap_err().to_string(),
+            "Function qtricks expects 2 arguments, got 3"
+        );
+        let ast = Expr::call_multi(Function::QuickTricks, Vec::new());
+        assert_eq!(
+            eval(&ast, &ctx).unwrap_err().to_string(),
+            "Function qtricks expects 1 arguments, got 0"
+        );
+    }
+
+    #[test]
+    fn test_eval_ahcp() {
+        // Seed 1 north: AKQT3.J6.KJ42.95 -> 14 HCP, Jx doubleton docked 0.5
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let ctx = EvalContext::new(&deal);
+
+        let ast = parse("ahcp(north)").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 27);
+
+        // Sound 13+ points, in halves
+        let ast = parse("ahcp(north) >= 26").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_eval_nltc() {
+        // Seed 1 north: AKQT3.J6.KJ42.95 -> 7 NLTC losers, in quarters
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let ctx = EvalContext::new(&deal);
+
+        let ast = parse("nltc(north)").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 28);
+
+        let ast = parse("nltc(north, hearts)").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 10);
+
+        let ast = parse("nltc(north) < 30").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 1);
+    }
+
     #[test]
     fn test_pt_synonyms() {
         let mut gen = DealGenerator::new(1);
@@ -1866,6 +2560,35 @@ mod tests {
         assert_eq!(result, north.suit_quality(Suit::Spades));
     }
 
+    #[test]
+    fn test_eval_quality_argument_count() {
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let ctx = EvalContext::new(&deal);
+
+        // quality() requires both a position and a suit
+        let ast = parse("quality(north)").unwrap();
+        assert!(matches!(
+            eval(&ast, &ctx),
+            Err(EvalError::InvalidArgumentCount {
+                expected: 2,
+                got: 1,
+                ..
+            })
+        ));
+
+        // cccc() takes only a position
+        let ast = parse("cccc(north, spades)").unwrap();
+        assert!(matches!(
+            eval(&ast, &ctx),
+            Err(EvalError::InvalidArgumentCount {
+                expected: 1,
+                got: 2,
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn test_eval_cccc() {
         let mut gen = DealGenerator::new(1);
@@ -1881,6 +2604,41 @@ mod tests {
         assert_eq!(result, north.cccc());
     }
 
+    #[test]
+    fn test_eval_zar() {
+        // Seed 1 north: AKQT3.J6.KJ42.95 -> 14 HCP + 4 controls + 12 distribution
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let ctx = EvalContext::new(&deal);
+
+        let ast = parse("zar(north)").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 30);
+
+        let ast = parse("zar(north) >= 26").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 1);
+
+        let ast = parse("zar(north, spades)").unwrap();
+        assert!(eval(&ast, &ctx).is_err());
+    }
+
+    #[test]
+    fn test_eval_distribution_points() {
+        // Seed 1 north AKQT3.J6.KJ42.95: two doubletons, one fifth spade
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let ctx = EvalContext::new(&deal);
+        let check = |input: &str| eval(&parse(input).unwrap(), &ctx).unwrap();
+
+        assert_eq!(check("dpoints(north)"), 2);
+        assert_eq!(check("lpoints(north)"), 1);
+        assert_eq!(check("hcp(north) + dpoints(north)"), 16);
+        // South J74.QT95.T.AK863: singleton diamond, fifth club
+        assert_eq!(check("dpoints(south)"), 2);
+        assert_eq!(check("lpoints(south)"), 1);
+
+        assert!(eval(&parse("dpoints(north, spades)").unwrap(), &ctx).is_err());
+    }
+
     #[test]
     fn test_cccc_constraint() {
         let mut gen = DealGenerator::new(42);
@@ -1991,6 +2749,62 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_eval_program_condition() {
+        use dealer_parser::parse_program;
+
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let north_hcp = deal.hand(Position::North).hcp() as i32;
+
+        // Accepted when the condition holds
+        let input = format!("condition hcp(north) >= {}", north_hcp);
+        let program = parse_program(&input).unwrap();
+        assert_eq!(eval_program(&program, &deal).unwrap(), 1);
+
+        // Rejected when it doesn't
+        let input = format!("condition hcp(north) >= {}", north_hcp + 1);
+        let program = parse_program(&input).unwrap();
+        assert_eq!(eval_program(&program, &deal).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_eval_program_multiple_conditions_are_anded() {
+        use dealer_parser::parse_program;
+
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let north_hcp = deal.hand(Position::North).hcp() as i32;
+
+        // Both conditions hold
+        let input = format!(
+            "condition hcp(north) >= {}\ncondition hcp(north) <= {}",
+            north_hcp, north_hcp
+        );
+        let program = parse_program(&input).unwrap();
+        assert_eq!(eval_program(&program, &deal).unwrap(), 1);
+
+        // Second condition fails, so the deal is rejected
+        let input = format!(
+            "condition hcp(north) >= {}\ncondition hcp(north) > {}",
+            north_hcp, north_hcp
+        );
+        let program = parse_program(&input).unwrap();
+        assert_eq!(eval_program(&program, &deal).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_eval_program_condition_overrides_bare_expression() {
+        use dealer_parser::parse_program;
+
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+
+        // The explicit condition wins over a trailing bare expression
+        let program = parse_program("condition 0\n1").unwrap();
+        assert_eq!(eval_program(&program, &deal).unwrap(), 0);
+    }
+
     #[test]
     fn test_eval_program_simple_variable() {
         use dealer_parser::parse_program;
@@ -2069,6 +2883,41 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_eval_program_variable_in_condition() {
+        use dealer_parser::parse_program;
+
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let combined = (deal.hand(Position::North).hcp() + deal.hand(Position::South).hcp()) as i32;
+
+        // Assignment referenced from a later condition statement
+        let input = format!("x = hcp(north) + hcp(south)\ncondition x >= {}", combined);
+        let program = parse_program(&input).unwrap();
+        assert_eq!(eval_program(&program, &deal).unwrap(), 1);
+
+        let input = format!(
+            "x = hcp(north) + hcp(south)\ncondition x >= {}",
+            combined + 1
+        );
+        let program = parse_program(&input).unwrap();
+        assert_eq!(eval_program(&program, &deal).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_eval_program_undefined_variable_in_condition() {
+        use dealer_parser::parse_program;
+
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+
+        // The error names the unknown identifier
+        let program = parse_program("x = hcp(north)\ncondition y >= 25").unwrap();
+        let err = eval_program(&program, &deal).unwrap_err();
+        assert_eq!(err, EvalError::UndefinedVariable("y".to_string()));
+        assert_eq!(err.to_string(), "Undefined variable: y");
+    }
+
     #[test]
     fn test_eval_program_undefined_variable() {
         use dealer_parser::parse_program;
@@ -2255,28 +3104,6 @@ mod tests {
         assert_eq!(result, expected);
     }
 
-    #[test]
-    fn test_score_to_imps() {
-        // Test IMP conversion with standard table
-        assert_eq!(score_to_imps(0), 0); // 0 points = 0 IMPs
-        assert_eq!(score_to_imps(9), 0); // < 10 = 0 IMPs
-        assert_eq!(score_to_imps(10), 1); // 10-39 = 1 IMP
-        assert_eq!(score_to_imps(39), 1);
-        assert_eq!(score_to_imps(40), 2); // 40-79 = 2 IMPs
-        assert_eq!(score_to_imps(79), 2);
-        assert_eq!(score_to_imps(80), 3); // 80-119 = 3 IMPs
-        assert_eq!(score_to_imps(410), 10); // 410-489 = 10 IMPs
-        assert_eq!(score_to_imps(420), 10); // 410-489 = 10 IMPs
-        assert_eq!(score_to_imps(490), 11); // 490-589 = 11 IMPs
-        assert_eq!(score_to_imps(1500), 17); // 1490-1739 = 17 IMPs
-        assert_eq!(score_to_imps(4000), 24); // 3990+ = 24 IMPs
-
-        // Test negative values (preserve sign)
-        assert_eq!(score_to_imps(-10), -1);
-        assert_eq!(score_to_imps(-420), -10);
-        assert_eq!(score_to_imps(-1500), -17);
-    }
-
     #[test]
     fn test_eval_imps() {
         use dealer_parser::parse;
@@ -2302,6 +3129,123 @@ mod tests {
         assert_eq!(eval(&ast, &ctx).unwrap(), 0);
     }
 
+    #[test]
+    fn test_metric_cache_avoids_rescan() {
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let ctx = EvalContext::new(&deal);
+
+        // hcp(north) appears three times but is only computed once
+        let ast = parse("hcp(north) >= 11 && hcp(north) <= 15 && hcp(north) != 13").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 1);
+        assert_eq!(ctx.metric_misses(), 1);
+
+        // Repeated evaluation of the same deal is served entirely from the cache
+        for _ in 0..1000 {
+            assert_eq!(eval(&ast, &ctx).unwrap(), 1);
+        }
+        assert_eq!(ctx.metric_misses(), 1);
+
+        // A different suit or position is a separate entry
+        let ast = parse("hcp(north, spades) + hcp(south) + spades(north) + spades(north)").unwrap();
+        eval(&ast, &ctx).unwrap();
+        assert_eq!(ctx.metric_misses(), 4);
+    }
+
+    #[test]
+    fn test_reset_cache_recomputes() {
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let ctx = EvalContext::new(&deal);
+
+        let ast = parse("hcp(north) + hcp(north) + spades(south)").unwrap();
+        let value = eval(&ast, &ctx).unwrap();
+        assert_eq!(ctx.metric_misses(), 2);
+        eval(&ast, &ctx).unwrap();
+        assert_eq!(ctx.metric_misses(), 2);
+
+        // After a reset the same metrics miss again, with the same values
+        ctx.reset_cache();
+        assert_eq!(eval(&ast, &ctx).unwrap(), value);
+        assert_eq!(ctx.metric_misses(), 4);
+    }
+
+    #[test]
+    fn test_metric_cache_matches_uncached() {
+        let mut gen = DealGenerator::new(7);
+        for deal in gen.generate_many(20) {
+            let ctx = EvalContext::new(&deal);
+            for pos in Position::ALL {
+                let name = match pos {
+                    Position::North => "north",
+                    Position::East => "east",
+                    Position::South => "south",
+                    Position::West => "west",
+                };
+                let hand = deal.hand(pos);
+                // Evaluate twice so the second value comes from the cache
+                for _ in 0..2 {
+                    let value = |src: String| eval(&parse(&src).unwrap(), &ctx).unwrap();
+                    assert_eq!(value(format!("hcp({})", name)), hand.hcp() as i32);
+                    assert_eq!(value(format!("controls({})", name)), hand.controls() as i32);
+                    assert_eq!(value(format!("losers({})", name)), hand.losers() as i32);
+                    assert_eq!(
+                        value(format!("hearts({})", name)),
+                        hand.suit_length(Suit::Hearts) as i32
+                    );
+                    assert_eq!(
+                        value(format!("hcp({}, clubs)", name)),
+                        hand.hcp_in_suit(Suit::Clubs) as i32
+                    );
+                    assert_eq!(
+                        value(format!("losers({}, diamonds)", name)),
+                        hand.losers_in_suit(Suit::Diamonds) as i32
+                    );
+                    assert_eq!(
+                        value(format!("controls({}, spades)", name)),
+                        hand.controls_in_suit(Suit::Spades) as i32
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_eval_between() {
+        use dealer_parser::parse;
+
+        // Seed 1 north: AKQT3.J6.KJ42.95 has 14 HCP
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let ctx = EvalContext::new(&deal);
+
+        // Both boundaries are inclusive
+        let ast = parse("between(hcp(north), 14, 17)").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 1);
+        let ast = parse("between(hcp(north), 11, 14)").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 1);
+
+        // Out of range
+        let ast = parse("between(hcp(north), 15, 17)").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 0);
+        let ast = parse("between(hcp(north), 10, 13)").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 0);
+
+        // Bounds can be expressions too
+        let ast = parse("between(hcp(north) + hcp(south), 20, hcp(north) * 2)").unwrap();
+        let ns = deal.north.hcp() as i32 + deal.south.hcp() as i32;
+        assert_eq!(
+            eval(&ast, &ctx).unwrap(),
+            i32::from((20..=28).contains(&ns))
+        );
+
+        let ast = parse("between(hcp(north), 10)").unwrap();
+        assert!(matches!(
+            eval(&ast, &ctx),
+            Err(EvalError::InvalidArgumentCount { expected: 3, .. })
+        ));
+    }
+
     #[test]
     fn test_contract_parse() {
         // Test contract parsing
@@ -2507,6 +3451,43 @@ mod tests {
         assert_eq!(eval(&ast, &ctx).unwrap(), -100);
     }
 
+    /// North holds every spade, East every heart, South every diamond, West every club
+    /// (card index = suit * 13 + rank, clubs first); quick to solve double-dummy
+    fn one_suit_deal() -> Deal {
+        use dealer_core::Card;
+
+        let mut deal = Deal::new();
+        for (pos, first) in [
+            (Position::North, 39),
+            (Position::East, 26),
+            (Position::South, 13),
+            (Position::West, 0),
+        ] {
+            for i in first..first + 13 {
+                deal.hand_mut(pos).add_card(Card::from_index(i).unwrap());
+            }
+        }
+        deal
+    }
+
+    #[test]
+    fn test_tricks_fixed_deal_cached() {
+        let deal = one_suit_deal();
+        let ctx = EvalContext::new(&deal);
+
+        let ast = parse("tricks(north, spades)").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 13);
+        // East leads hearts and never loses the lead
+        let ast = parse("tricks(north, notrumps)").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 0);
+        assert_eq!(ctx.dd_solves(), 2);
+
+        // Repeats and the numeric spelling hit the cache
+        let ast = parse("tricks(north, spades) + tricks(north, 3) + tricks(n, notrump)").unwrap();
+        assert_eq!(eval(&ast, &ctx).unwrap(), 26);
+        assert_eq!(ctx.dd_solves(), 2);
+    }
+
     #[test]
     #[ignore] // Slow: requires DDS solver (~1 sec per call)
     fn test_eval_tricks() {
@@ -2546,6 +3527,30 @@ mod tests {
         // Both should be valid (0-13) - we already checked above
     }
 
+    #[test]
+    fn test_ddscore() {
+        // Fill the trick cache first, so the contracts below are scored from it
+        let deal = one_suit_deal();
+        let ctx = EvalContext::new(&deal);
+        assert_eq!(ctx.cached_tricks(Position::North, Denomination::Spades), 13);
+        assert_eq!(ctx.cached_tricks(Position::North, Denomination::Hearts), 0);
+        assert_eq!(ctx.cached_tricks(Position::East, Denomination::Hearts), 13);
+        let check = |input: &str| eval(&parse(input).unwrap(), &ctx).unwrap();
+
+        // 4S vulnerable with three overtricks
+        assert_eq!(check("ddscore(north, spades, 4, 1)"), 710);
+        // Non-vulnerable 4H ten down: East's trumps take every trick
+        assert_eq!(check("ddscore(north, hearts, 4, 0)"), -500);
+        assert_eq!(check("ddscore(east, hearts, 4, 0)"), 510);
+        // Numeric denomination; 3S is a part score, plus four overtricks
+        assert_eq!(check("ddscore(north, 3, 3, 0)"), 260);
+        // Only the three cached results were used
+        assert_eq!(ctx.dd_solves(), 3);
+
+        assert!(eval(&parse("ddscore(north, spades, 8, 0)").unwrap(), &ctx).is_err());
+        assert!(eval(&parse("ddscore(north, spades, 4)").unwrap(), &ctx).is_err());
+    }
+
     #[test]
     #[ignore] // Slow: requires DDS solver (~1 sec per call)
     fn test_tricks_with_score() {
@@ -1,4 +1,5 @@
 use dealer_core::Position;
+use std::fmt;
 
 /// A program consists of multiple statements
 #[derive(Debug, Clone, PartialEq)]
@@ -30,6 +31,11 @@ pub enum Statement {
     Dealer(Position),
     /// Vulnerable statement: vulnerable none/NS/EW/all
     Vulnerable(VulnerabilityType),
+    /// Evalcontract statement: list the contracts each side can make on every produced deal
+    EvalContract,
+    /// Rotate statement: rotate on/off
+    /// Off pins dealer and vulnerability to North/None unless set explicitly
+    Rotate(bool),
     /// Predeal statement: predeal N/E/S/W cards
     Predeal {
         position: Position,
@@ -55,11 +61,7 @@ pub enum CsvTerm {
 }
 
 /// Side enumeration for CSV output
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Side {
-    NS,
-    EW,
-}
+pub use dealer_core::Side;
 
 /// An average specification within an action statement
 #[derive(Debug, Clone, PartialEq)]
@@ -99,13 +101,25 @@ impl VulnerabilityType {
 }
 
 /// Action types for output formatting
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ActionType {
     PrintAll,
     PrintEW,
     PrintPBN,
-    PrintCompact,
+    /// printcompact with an optional value printed after each deal
+    PrintCompact(Option<Expr>),
     PrintOneLine,
+    /// printf with a template rendered after each deal
+    PrintF(Vec<TemplatePart>),
+}
+
+/// A piece of a printf template
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplatePart {
+    /// Literal text, with %% already unescaped to %
+    Text(String),
+    /// A %{expr} substitution, replaced by the expression's value
+    Expr(Expr),
 }
 
 impl ActionType {
@@ -114,7 +128,7 @@ impl ActionType {
             "printall" => Some(ActionType::PrintAll),
             "printew" => Some(ActionType::PrintEW),
             "printpbn" => Some(ActionType::PrintPBN),
-            "printcompact" => Some(ActionType::PrintCompact),
+            "printcompact" => Some(ActionType::PrintCompact(None)),
             "printoneline" => Some(ActionType::PrintOneLine),
             _ => None,
         }
@@ -151,6 +165,9 @@ pub enum Expr {
     /// Position identifier (north, south, east, west)
     Position(Position),
 
+    /// Partnership (ns, ew) as the first argument of hcp(), spades() and the like
+    Side(Side),
+
     /// Shape pattern for matching hand distributions
     ShapePattern(ShapePattern),
 
@@ -160,6 +177,9 @@ pub enum Expr {
     /// Suit literal (spades, hearts, diamonds, clubs)
     Suit(dealer_core::Suit),
 
+    /// Notrump denomination literal (notrump, notrumps)
+    NoTrump,
+
     /// Variable reference (e.g., nt_opener, weak_hand)
     Variable(String),
 }
@@ -183,26 +203,7 @@ impl ShapePattern {
 
     /// Compute and cache the shape mask.
     fn compute_mask(&mut self) {
-        use dealer_core::ShapeMask;
-
-        let mut result = ShapeMask::empty();
-
-        for spec in &self.specs {
-            let spec_mask = match &spec.shape {
-                Shape::Exact(p) => ShapeMask::exact(p[0], p[1], p[2], p[3]),
-                Shape::Wildcard(p) => ShapeMask::wildcard(*p),
-                Shape::AnyDistribution(p) => ShapeMask::any_distribution(*p),
-                Shape::AnyWildcard(p) => ShapeMask::any_wildcard(*p),
-            };
-
-            if spec.include {
-                result = result.union(&spec_mask);
-            } else {
-                result = result.difference(&spec_mask);
-            }
-        }
-
-        self.mask = Some(result);
+        self.mask = Some(specs_mask(&self.specs));
     }
 
     /// Get the precomputed shape mask.
@@ -218,6 +219,36 @@ impl ShapePattern {
     }
 }
 
+/// Combine specs left to right, recursing into groups
+fn specs_mask(specs: &[ShapeSpec]) -> dealer_core::ShapeMask {
+    use dealer_core::ShapeMask;
+
+    let mut result = ShapeMask::empty();
+
+    for spec in specs {
+        let spec_mask = match &spec.shape {
+            Shape::Exact(p) => ShapeMask::exact(p[0], p[1], p[2], p[3]),
+            Shape::Wildcard(p) => ShapeMask::wildcard(*p),
+            Shape::AnyDistribution(p) => ShapeMask::any_distribution(*p),
+            Shape::AnyWildcard(p) => ShapeMask::any_wildcard(*p),
+            Shape::Range(r) => ShapeMask::range(*r),
+            Shape::Constraint { ranges, any_other } => {
+                let other_mins: Vec<u8> = any_other.iter().flatten().copied().collect();
+                ShapeMask::constraint(*ranges, &other_mins)
+            }
+            Shape::Group(inner) => specs_mask(inner),
+        };
+
+        if spec.include {
+            result = result.union(&spec_mask);
+        } else {
+            result = result.difference(&spec_mask);
+        }
+    }
+
+    result
+}
+
 /// A single shape specification (possibly with operators)
 #[derive(Debug, Clone, PartialEq)]
 pub struct ShapeSpec {
@@ -238,6 +269,20 @@ pub enum Shape {
     AnyDistribution([u8; 4]),
     /// Any wildcard: "any 6xxx" means any distribution with 6 in some suit (any permutation of wildcard)
     AnyWildcard([Option<u8>; 4]),
+    /// Range shape: "5+ 4+ x x" means at least 5 spades and at least 4 hearts.
+    /// Each suit is (min, max); None means unbounded on that side
+    Range([(Option<u8>, Option<u8>); 4]),
+    /// Constraint shape: "5+ any4+ x x" means at least 5 spades and at least one
+    /// other suit with 4+ cards. `ranges` holds the suit bounds as for `Range` (an
+    /// "anyN+" slot is unbounded); each `Some(n)` in `any_other` is an "anyN+" slot
+    /// that needs its own suit among those the pattern leaves unbounded
+    Constraint {
+        ranges: [(Option<u8>, Option<u8>); 4],
+        any_other: [Option<u8>; 4],
+    },
+    /// Parenthesized group: "(4333 + 4432)", combined as a unit with the specs around it.
+    /// "any(...)" is stored with "any" already applied to each member
+    Group(Vec<ShapeSpec>),
 }
 
 /// Binary operators
@@ -271,10 +316,12 @@ pub enum UnaryOp {
 }
 
 /// Built-in functions for hand evaluation
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Function {
     /// High Card Points (A=4, K=3, Q=2, J=1)
     Hcp,
+    /// HCP adjusted for short honors, in halves (singleton K/Q/J -2, Qx/Jx -1)
+    HcpAdjusted,
 
     /// Number of spades
     Spades,
@@ -293,12 +340,18 @@ pub enum Function {
 
     /// Losers count
     Losers,
+    /// New Losing Trick Count losers in quarters (missing A=6, K=4, Q=2)
+    NewLosers,
+    /// Losers less those covered by another hand's honors: coverlosers(position, partner)
+    CoverLosers,
 
     /// Shape analysis
     Shape,
 
     /// Has specific card
     HasCard,
+    /// Has at least one of several cards
+    HasAnyCard,
 
     // Alternative point counts (pt0-pt9)
     /// Number of tens
@@ -319,22 +372,52 @@ pub enum Function {
     Top4,
     /// Top 5 honors (AKQJT)
     Top5,
+    /// Top N ranks of a suit: top(position, suit, n)
+    Top,
+    /// Intermediate spot cards (T, 9, 8) in a suit
+    Intermediates,
     /// C13 point count (A=6, K=4, Q=2, J=1)
     C13,
+    /// Quick tricks in halves (AK=4, AQ=3, A/KQ=2, Kx=1)
+    QuickTricks,
 
     // Hand quality functions
-    /// Quality metric for a suit (Bridge World Oct 1982)
+    /// Quality metric for a suit (Bridge World Oct 1982), scaled by 100
     Quality,
-    /// CCCC evaluation algorithm (Bridge World Oct 1982)
+    /// CCCC evaluation algorithm (Bridge World Oct 1982), scaled by 100
     Cccc,
+    /// Zar points: HCP + controls + distribution
+    Zar,
+    /// Shortness points: void 3, singleton 2, doubleton 1
+    ShortnessPoints,
+    /// Length points: 1 for each card beyond four in a suit
+    LengthPoints,
+    /// Length of the longest suit
+    Longest,
+    /// Length of the shortest suit
+    Shortest,
+    /// 1 if the hand is balanced (4333, 4432, 5332)
+    Balanced,
+    /// 1 if the hand is semi-balanced (balanced, 5422, 6322)
+    SemiBalanced,
 
     // Double-dummy and scoring functions
     /// Double-dummy trick count
     Tricks,
     /// Contract score calculation
     Score,
+    /// Score of a contract at its double-dummy trick count
+    DdScore,
     /// Convert score difference to IMPs
     Imps,
+
+    // Convenience functions
+    /// Range test: 1 if low <= value <= high (all three are expressions)
+    Between,
+    /// Partner of a position; usable wherever a position argument is expected
+    Partner,
+    /// Combined length of a suit in a partnership: fit(ns, spades)
+    Fit,
 }
 
 impl Function {
@@ -347,9 +430,13 @@ impl Function {
             "diamonds" | "diamond" => Some(Function::Diamonds),
             "clubs" | "club" => Some(Function::Clubs),
             "controls" => Some(Function::Controls),
+            "ahcp" => Some(Function::HcpAdjusted),
             "losers" | "loser" => Some(Function::Losers),
+            "nltc" => Some(Function::NewLosers),
+            "coverlosers" => Some(Function::CoverLosers),
             "shape" => Some(Function::Shape),
             "hascard" => Some(Function::HasCard),
+            "hasanycard" => Some(Function::HasAnyCard),
             "tens" | "pt0" => Some(Function::Tens),
             "jacks" | "pt1" => Some(Function::Jacks),
             "queens" | "pt2" => Some(Function::Queens),
@@ -359,15 +446,316 @@ impl Function {
             "top3" | "pt6" => Some(Function::Top3),
             "top4" | "pt7" => Some(Function::Top4),
             "top5" | "pt8" => Some(Function::Top5),
+            "top" => Some(Function::Top),
+            "intermediates" => Some(Function::Intermediates),
             "c13" | "pt9" => Some(Function::C13),
+            "qtricks" => Some(Function::QuickTricks),
             "quality" => Some(Function::Quality),
             "cccc" => Some(Function::Cccc),
+            "zar" => Some(Function::Zar),
+            "dpoints" => Some(Function::ShortnessPoints),
+            "lpoints" => Some(Function::LengthPoints),
+            "longest" => Some(Function::Longest),
+            "shortest" => Some(Function::Shortest),
+            "balanced" => Some(Function::Balanced),
+            "semibalanced" => Some(Function::SemiBalanced),
             "tricks" => Some(Function::Tricks),
             "score" => Some(Function::Score),
+            "ddscore" => Some(Function::DdScore),
             "imps" => Some(Function::Imps),
+            "between" => Some(Function::Between),
+            "partner" => Some(Function::Partner),
+            "fit" => Some(Function::Fit),
             _ => None,
         }
     }
+
+    /// Canonical script name, the form `Display` prints
+    pub fn name(&self) -> &'static str {
+        match self {
+            Function::Hcp => "hcp",
+            Function::HcpAdjusted => "ahcp",
+            Function::Spades => "spades",
+            Function::Hearts => "hearts",
+            Function::Diamonds => "diamonds",
+            Function::Clubs => "clubs",
+            Function::Controls => "controls",
+            Function::Losers => "losers",
+            Function::NewLosers => "nltc",
+            Function::CoverLosers => "coverlosers",
+            Function::Shape => "shape",
+            Function::HasCard => "hascard",
+            Function::HasAnyCard => "hasanycard",
+            Function::Tens => "tens",
+            Function::Jacks => "jacks",
+            Function::Queens => "queens",
+            Function::Kings => "kings",
+            Function::Aces => "aces",
+            Function::Top2 => "top2",
+            Function::Top3 => "top3",
+            Function::Top4 => "top4",
+            Function::Top5 => "top5",
+            Function::Top => "top",
+            Function::Intermediates => "intermediates",
+            Function::C13 => "c13",
+            Function::QuickTricks => "qtricks",
+            Function::Quality => "quality",
+            Function::Cccc => "cccc",
+            Function::Zar => "zar",
+            Function::ShortnessPoints => "dpoints",
+            Function::LengthPoints => "lpoints",
+            Function::Longest => "longest",
+            Function::Shortest => "shortest",
+            Function::Balanced => "balanced",
+            Function::SemiBalanced => "semibalanced",
+            Function::Tricks => "tricks",
+            Function::Score => "score",
+            Function::DdScore => "ddscore",
+            Function::Imps => "imps",
+            Function::Between => "between",
+            Function::Partner => "partner",
+            Function::Fit => "fit",
+        }
+    }
+}
+
+impl BinaryOp {
+    /// Source symbol of the operator
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            BinaryOp::And => "&&",
+            BinaryOp::Or => "||",
+            BinaryOp::Eq => "==",
+            BinaryOp::Ne => "!=",
+            BinaryOp::Lt => "<",
+            BinaryOp::Le => "<=",
+            BinaryOp::Gt => ">",
+            BinaryOp::Ge => ">=",
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Mod => "%",
+        }
+    }
+
+    /// Grammar precedence level; higher binds tighter
+    fn precedence(&self) -> u8 {
+        match self {
+            BinaryOp::Or => PREC_OR,
+            BinaryOp::And => PREC_AND,
+            BinaryOp::Eq
+            | BinaryOp::Ne
+            | BinaryOp::Lt
+            | BinaryOp::Le
+            | BinaryOp::Gt
+            | BinaryOp::Ge => PREC_COMPARISON,
+            BinaryOp::Add | BinaryOp::Sub => PREC_ADDITIVE,
+            BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => PREC_MULTIPLICATIVE,
+        }
+    }
+}
+
+// Precedence levels of the expression grammar, loosest first
+const PREC_TERNARY: u8 = 1;
+const PREC_OR: u8 = 2;
+const PREC_AND: u8 = 3;
+const PREC_COMPARISON: u8 = 5;
+const PREC_ADDITIVE: u8 = 6;
+const PREC_MULTIPLICATIVE: u8 = 7;
+const PREC_UNARY: u8 = 8;
+const PREC_PRIMARY: u8 = 9;
+
+/// Prints the canonical source form: `hcp(north) >= 15 && shape(north, 5332)`
+///
+/// Parentheses appear only where precedence requires them, so the output
+/// parses back (after `preprocess()`) to an equal tree. Operators use their
+/// symbol forms (`&&`, `||`, `!`) and functions their canonical names.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_within(f, 0)
+    }
+}
+
+impl Expr {
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Ternary { .. } => PREC_TERNARY,
+            Expr::BinaryOp { op, .. } => op.precedence(),
+            Expr::UnaryOp { .. } => PREC_UNARY,
+            _ => PREC_PRIMARY,
+        }
+    }
+
+    /// Write the expression, parenthesized if it binds looser than `min_precedence`
+    fn fmt_within(&self, f: &mut fmt::Formatter, min_precedence: u8) -> fmt::Result {
+        let parens = self.precedence() < min_precedence;
+        if parens {
+            write!(f, "(")?;
+        }
+
+        match self {
+            Expr::Ternary {
+                condition,
+                true_expr,
+                false_expr,
+            } => {
+                condition.fmt_within(f, PREC_OR)?;
+                write!(f, " ? ")?;
+                true_expr.fmt_within(f, PREC_OR)?;
+                write!(f, " : ")?;
+                false_expr.fmt_within(f, PREC_TERNARY)?;
+            }
+            Expr::BinaryOp { op, left, right } => {
+                // Operators group left to right, and a comparison inside a
+                // comparison would read as a chain, so it needs parentheses too
+                let precedence = op.precedence();
+                let left_min = if precedence == PREC_COMPARISON {
+                    precedence + 1
+                } else {
+                    precedence
+                };
+                left.fmt_within(f, left_min)?;
+                write!(f, " {} ", op.symbol())?;
+                right.fmt_within(f, precedence + 1)?;
+            }
+            Expr::UnaryOp { op, expr } => {
+                let symbol = match op {
+                    UnaryOp::Not => "!",
+                    UnaryOp::Negate => "-",
+                };
+                write!(f, "{}", symbol)?;
+                expr.fmt_within(f, PREC_UNARY)?;
+            }
+            Expr::FunctionCall { func, args } => {
+                write!(f, "{}(", func.name())?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    arg.fmt_within(f, 0)?;
+                }
+                write!(f, ")")?;
+            }
+            Expr::Literal(value) => write!(f, "{}", value)?,
+            Expr::Position(position) => write!(f, "{}", position_name(*position))?,
+            Expr::Side(side) => write!(
+                f,
+                "{}",
+                match side {
+                    Side::NS => "ns",
+                    Side::EW => "ew",
+                }
+            )?,
+            Expr::ShapePattern(pattern) => write!(f, "{}", pattern)?,
+            Expr::Card(card) => write!(f, "{}{}", card.rank.to_char(), suit_letter(card.suit))?,
+            Expr::Suit(suit) => write!(f, "{}", suit_name(*suit))?,
+            Expr::NoTrump => write!(f, "notrump")?,
+            Expr::Variable(name) => write!(f, "{}", name)?,
+        }
+
+        if parens {
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ShapePattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_shape_specs(f, &self.specs)
+    }
+}
+
+fn fmt_shape_specs(f: &mut fmt::Formatter, specs: &[ShapeSpec]) -> fmt::Result {
+    for (i, spec) in specs.iter().enumerate() {
+        match (i, spec.include) {
+            (0, true) => {}
+            (0, false) => write!(f, "- ")?,
+            (_, true) => write!(f, " + ")?,
+            (_, false) => write!(f, " - ")?,
+        }
+        let lengths = |f: &mut fmt::Formatter, pattern: &[Option<u8>; 4]| {
+            pattern.iter().try_for_each(|length| match length {
+                Some(n) => write!(f, "{}", n),
+                None => write!(f, "x"),
+            })
+        };
+        match &spec.shape {
+            Shape::Exact(p) => lengths(f, &p.map(Some))?,
+            Shape::Wildcard(p) => lengths(f, p)?,
+            Shape::AnyDistribution(p) => {
+                write!(f, "any ")?;
+                lengths(f, &p.map(Some))?;
+            }
+            Shape::AnyWildcard(p) => {
+                write!(f, "any ")?;
+                lengths(f, p)?;
+            }
+            Shape::Range(ranges) => fmt_shape_ranges(f, ranges, &[None; 4])?,
+            Shape::Constraint { ranges, any_other } => fmt_shape_ranges(f, ranges, any_other)?,
+            Shape::Group(inner) => {
+                write!(f, "(")?;
+                fmt_shape_specs(f, inner)?;
+                write!(f, ")")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write range shape lengths, with "anyN+" in the `any_other` slots
+fn fmt_shape_ranges(
+    f: &mut fmt::Formatter,
+    ranges: &[(Option<u8>, Option<u8>); 4],
+    any_other: &[Option<u8>; 4],
+) -> fmt::Result {
+    // The grammar needs at least one "n+", "n-m" or "anyN+" element, so an
+    // all-exact range keeps its bounds spelled out as "n-n"
+    let has_bound = any_other.iter().any(Option::is_some)
+        || ranges
+            .iter()
+            .any(|range| !matches!(range, (None, None)) && range.0 != range.1);
+    for (j, (range, other)) in ranges.iter().zip(any_other).enumerate() {
+        if j > 0 {
+            write!(f, " ")?;
+        }
+        match (range, other) {
+            (_, Some(min)) => write!(f, "any{}+", min)?,
+            ((None, None), None) => write!(f, "x")?,
+            ((Some(min), None), None) => write!(f, "{}+", min)?,
+            ((Some(min), Some(max)), None) if min == max && has_bound => write!(f, "{}", min)?,
+            ((min, Some(max)), None) => write!(f, "{}-{}", min.unwrap_or(0), max)?,
+        }
+    }
+    Ok(())
+}
+
+fn position_name(position: Position) -> &'static str {
+    match position {
+        Position::North => "north",
+        Position::East => "east",
+        Position::South => "south",
+        Position::West => "west",
+    }
+}
+
+fn suit_name(suit: dealer_core::Suit) -> &'static str {
+    match suit {
+        dealer_core::Suit::Spades => "spades",
+        dealer_core::Suit::Hearts => "hearts",
+        dealer_core::Suit::Diamonds => "diamonds",
+        dealer_core::Suit::Clubs => "clubs",
+    }
+}
+
+fn suit_letter(suit: dealer_core::Suit) -> char {
+    match suit {
+        dealer_core::Suit::Spades => 'S',
+        dealer_core::Suit::Hearts => 'H',
+        dealer_core::Suit::Diamonds => 'D',
+        dealer_core::Suit::Clubs => 'C',
+    }
 }
 
 impl Expr {
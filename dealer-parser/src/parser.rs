@@ -1,5 +1,6 @@
 use crate::ast::*;
-use dealer_core::Position;
+use crate::preprocess::preprocess;
+use dealer_core::{Position, RankExt, SuitExt};
 use pest::iterators::Pair;
 use pest::Parser;
 use pest_derive::Parser;
@@ -167,6 +168,12 @@ fn build_statement(pair: Pair<Rule>) -> Result<Statement, ParseError> {
                                     })?;
                                 format = Some(action_type);
                             }
+                            Rule::printcompact_spec => {
+                                format = Some(build_printcompact(comp_inner)?);
+                            }
+                            Rule::printf_spec => {
+                                format = Some(build_printf(comp_inner)?);
+                            }
                             _ => {
                                 return Err(ParseError {
                                     message: format!(
@@ -213,6 +220,11 @@ fn build_statement(pair: Pair<Rule>) -> Result<Statement, ParseError> {
             })?;
             Ok(Statement::Vulnerable(vuln))
         }
+        Rule::evalcontract_stmt => Ok(Statement::EvalContract),
+        Rule::rotate_stmt => {
+            let setting = inner.into_inner().next().unwrap().as_str();
+            Ok(Statement::Rotate(setting.eq_ignore_ascii_case("on")))
+        }
         Rule::predeal_stmt => {
             let mut parts = inner.into_inner();
 
@@ -375,9 +387,15 @@ fn build_statement(pair: Pair<Rule>) -> Result<Statement, ParseError> {
         }
         Rule::print_stmt => {
             // Standalone print statement: printpbn, printall, etc.
-            let action_type = ActionType::parse(inner.as_str()).ok_or_else(|| ParseError {
-                message: format!("Invalid print statement: {}", inner.as_str()),
-            })?;
+            let action_type = match inner.clone().into_inner().next() {
+                Some(spec) if spec.as_rule() == Rule::printcompact_spec => {
+                    build_printcompact(spec)?
+                }
+                Some(spec) if spec.as_rule() == Rule::printf_spec => build_printf(spec)?,
+                _ => ActionType::parse(inner.as_str()).ok_or_else(|| ParseError {
+                    message: format!("Invalid print statement: {}", inner.as_str()),
+                })?,
+            };
             Ok(Statement::Action {
                 averages: Vec::new(),
                 frequencies: Vec::new(),
@@ -400,6 +418,72 @@ fn build_statement(pair: Pair<Rule>) -> Result<Statement, ParseError> {
     }
 }
 
+/// Build a printcompact action with its optional trailing value expression
+fn build_printcompact(pair: Pair<Rule>) -> Result<ActionType, ParseError> {
+    // The first child is the keyword; an expression may follow
+    let value = match pair.into_inner().nth(1) {
+        Some(expr_pair) => Some(build_ast(expr_pair)?),
+        None => None,
+    };
+    Ok(ActionType::PrintCompact(value))
+}
+
+fn build_printf(pair: Pair<Rule>) -> Result<ActionType, ParseError> {
+    // The first child is the keyword, the second the quoted template
+    let literal = pair
+        .into_inner()
+        .nth(1)
+        .ok_or_else(|| ParseError {
+            message: "printf requires a quoted template".to_string(),
+        })?
+        .as_str();
+    let template = &literal[1..literal.len() - 1];
+    Ok(ActionType::PrintF(parse_template(template)?))
+}
+
+/// Split a printf template into literal text and %{expr} substitutions.
+/// %% is a literal percent sign; any other escape is an error.
+fn parse_template(template: &str) -> Result<Vec<TemplatePart>, ParseError> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut rest = template;
+
+    while let Some(pos) = rest.find('%') {
+        text.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        if let Some(tail) = after.strip_prefix('%') {
+            text.push('%');
+            rest = tail;
+        } else if let Some(body) = after.strip_prefix('{') {
+            let end = body.find('}').ok_or_else(|| ParseError {
+                message: format!("Unterminated %{{ in printf template: {}", template),
+            })?;
+            if !text.is_empty() {
+                parts.push(TemplatePart::Text(std::mem::take(&mut text)));
+            }
+            // preprocess() leaves string literals alone, so shapes are marked here
+            let expr = parse(&preprocess(&body[..end])).map_err(|e| ParseError {
+                message: format!("Invalid expression in printf template: {}", e.message),
+            })?;
+            parts.push(TemplatePart::Expr(expr));
+            rest = &body[end + 1..];
+        } else {
+            return Err(ParseError {
+                message: format!(
+                    "Invalid escape in printf template (use %{{expr}} or %%): {}",
+                    template
+                ),
+            });
+        }
+    }
+
+    text.push_str(rest);
+    if !text.is_empty() {
+        parts.push(TemplatePart::Text(text));
+    }
+    Ok(parts)
+}
+
 /// Parse a single card from a string like "AS", "KH", "2C" (rank+suit format for hascard)
 fn parse_card(card_str: &str) -> Result<dealer_core::Card, ParseError> {
     if card_str.len() != 2 {
@@ -408,42 +492,17 @@ fn parse_card(card_str: &str) -> Result<dealer_core::Card, ParseError> {
         });
     }
 
-    let chars: Vec<char> = card_str.chars().collect();
+    // Card literals are case-insensitive: "as" is the same as "AS"
+    let chars: Vec<char> = card_str.to_ascii_uppercase().chars().collect();
     let rank_char = chars[0];
     let suit_char = chars[1];
 
-    let rank = match rank_char {
-        'A' => dealer_core::Rank::Ace,
-        'K' => dealer_core::Rank::King,
-        'Q' => dealer_core::Rank::Queen,
-        'J' => dealer_core::Rank::Jack,
-        'T' => dealer_core::Rank::Ten,
-        '9' => dealer_core::Rank::Nine,
-        '8' => dealer_core::Rank::Eight,
-        '7' => dealer_core::Rank::Seven,
-        '6' => dealer_core::Rank::Six,
-        '5' => dealer_core::Rank::Five,
-        '4' => dealer_core::Rank::Four,
-        '3' => dealer_core::Rank::Three,
-        '2' => dealer_core::Rank::Two,
-        _ => {
-            return Err(ParseError {
-                message: format!("Invalid rank: {}", rank_char),
-            })
-        }
-    };
-
-    let suit = match suit_char {
-        'S' => dealer_core::Suit::Spades,
-        'H' => dealer_core::Suit::Hearts,
-        'D' => dealer_core::Suit::Diamonds,
-        'C' => dealer_core::Suit::Clubs,
-        _ => {
-            return Err(ParseError {
-                message: format!("Invalid suit: {}", suit_char),
-            })
-        }
-    };
+    let rank = dealer_core::Rank::from_char(rank_char).ok_or_else(|| ParseError {
+        message: format!("Invalid rank: {}", rank_char),
+    })?;
+    let suit = dealer_core::Suit::from_char(suit_char).ok_or_else(|| ParseError {
+        message: format!("Invalid suit: {}", suit_char),
+    })?;
 
     Ok(dealer_core::Card::new(suit, rank))
 }
@@ -458,43 +517,18 @@ fn parse_cards(card_str: &str) -> Result<Vec<dealer_core::Card>, ParseError> {
         });
     }
 
-    let chars: Vec<char> = card_str.chars().collect();
+    let chars: Vec<char> = card_str.to_ascii_uppercase().chars().collect();
     let suit_char = chars[0];
 
-    let suit = match suit_char {
-        'S' => dealer_core::Suit::Spades,
-        'H' => dealer_core::Suit::Hearts,
-        'D' => dealer_core::Suit::Diamonds,
-        'C' => dealer_core::Suit::Clubs,
-        _ => {
-            return Err(ParseError {
-                message: format!("Invalid suit: {}", suit_char),
-            })
-        }
-    };
+    let suit = dealer_core::Suit::from_char(suit_char).ok_or_else(|| ParseError {
+        message: format!("Invalid suit: {}", suit_char),
+    })?;
 
     let mut cards = Vec::new();
     for &rank_char in &chars[1..] {
-        let rank = match rank_char {
-            'A' => dealer_core::Rank::Ace,
-            'K' => dealer_core::Rank::King,
-            'Q' => dealer_core::Rank::Queen,
-            'J' => dealer_core::Rank::Jack,
-            'T' => dealer_core::Rank::Ten,
-            '9' => dealer_core::Rank::Nine,
-            '8' => dealer_core::Rank::Eight,
-            '7' => dealer_core::Rank::Seven,
-            '6' => dealer_core::Rank::Six,
-            '5' => dealer_core::Rank::Five,
-            '4' => dealer_core::Rank::Four,
-            '3' => dealer_core::Rank::Three,
-            '2' => dealer_core::Rank::Two,
-            _ => {
-                return Err(ParseError {
-                    message: format!("Invalid rank: {}", rank_char),
-                })
-            }
-        };
+        let rank = dealer_core::Rank::from_char(rank_char).ok_or_else(|| ParseError {
+            message: format!("Invalid rank: {}", rank_char),
+        })?;
         cards.push(dealer_core::Card::new(suit, rank));
     }
 
@@ -680,7 +714,7 @@ fn build_ast(pair: Pair<Rule>) -> Result<Expr, ParseError> {
             build_ast(inner)
         }
 
-        Rule::function_call => {
+        Rule::function_call | Rule::side_call | Rule::shape_call => {
             let mut pairs = pair.into_inner();
             let func_name = pairs.next().unwrap().as_str();
 
@@ -694,6 +728,18 @@ fn build_ast(pair: Pair<Rule>) -> Result<Expr, ParseError> {
                 message: format!("Unknown function: {}", func_name),
             })?;
 
+            // A lowercase card name ("as", "tc") parses as a variable; only
+            // where a card is expected is it read as the card
+            if matches!(func, Function::HasCard | Function::HasAnyCard) {
+                for arg in args.iter_mut().skip(1) {
+                    if let Expr::Variable(name) = arg {
+                        if let Ok(card) = parse_card(name) {
+                            *arg = Expr::Card(card);
+                        }
+                    }
+                }
+            }
+
             Ok(Expr::call_multi(func, args))
         }
 
@@ -704,6 +750,11 @@ fn build_ast(pair: Pair<Rule>) -> Result<Expr, ParseError> {
             })
         }
 
+        Rule::side => match pair.as_str().to_lowercase().as_str() {
+            "ns" => Ok(Expr::Side(Side::NS)),
+            _ => Ok(Expr::Side(Side::EW)),
+        },
+
         Rule::position => {
             let pos_str = pair.as_str().to_lowercase();
             let position = match pos_str.as_str() {
@@ -729,6 +780,11 @@ fn build_ast(pair: Pair<Rule>) -> Result<Expr, ParseError> {
 
         Rule::card => {
             let card_str = pair.as_str();
+            // Outside hascard/hasanycard only uppercase names are cards, so
+            // two-letter variables like "th" or "as" keep working
+            if card_str.chars().any(|c| c.is_ascii_lowercase()) {
+                return Ok(Expr::Variable(card_str.to_string()));
+            }
             let card = parse_card(card_str)?;
             Ok(Expr::Card(card))
         }
@@ -748,27 +804,11 @@ fn build_ast(pair: Pair<Rule>) -> Result<Expr, ParseError> {
             };
             Ok(Expr::Suit(suit))
         }
+        Rule::notrump => Ok(Expr::NoTrump),
 
-        Rule::shape_pattern => {
-            let mut specs = Vec::new();
-            let mut include = true; // First spec is always included
-
-            for inner_pair in pair.into_inner() {
-                match inner_pair.as_rule() {
-                    Rule::shape_spec => {
-                        let shape = parse_shape_spec(inner_pair)?;
-                        specs.push(ShapeSpec { include, shape });
-                        include = true; // Reset for next spec
-                    }
-                    Rule::shape_op => {
-                        include = inner_pair.as_str() == "+";
-                    }
-                    _ => {}
-                }
-            }
-
-            Ok(Expr::ShapePattern(ShapePattern::new(specs)))
-        }
+        Rule::shape_pattern | Rule::shape_arg => Ok(Expr::ShapePattern(ShapePattern::new(
+            parse_shape_specs(pair)?,
+        ))),
 
         Rule::ident => {
             // Variable reference
@@ -782,6 +822,76 @@ fn build_ast(pair: Pair<Rule>) -> Result<Expr, ParseError> {
     }
 }
 
+/// Parse the specs of a shape pattern, e.g. "any 4333 + 54xx - (5xxx + x5xx)"
+fn parse_shape_specs(pair: Pair<Rule>) -> Result<Vec<ShapeSpec>, ParseError> {
+    let mut specs = Vec::new();
+    let mut include = true; // First spec is always included
+
+    for inner_pair in pair.into_inner() {
+        match inner_pair.as_rule() {
+            Rule::shape_spec => {
+                let shape = parse_shape_spec(inner_pair)?;
+                specs.push(ShapeSpec { include, shape });
+                include = true; // Reset for next spec
+            }
+            Rule::shape_range => {
+                let shape = parse_shape_range(inner_pair)?;
+                specs.push(ShapeSpec { include, shape });
+                include = true;
+            }
+            Rule::shape_op => {
+                include = inner_pair.as_str() == "+";
+            }
+            _ => {}
+        }
+    }
+
+    Ok(specs)
+}
+
+/// Parse a parenthesized shape group, applying a leading "any" to every member
+fn parse_shape_group(pair: Pair<Rule>) -> Result<Shape, ParseError> {
+    let mut is_any = false;
+    let mut specs = Vec::new();
+
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::shape_any => is_any = true,
+            Rule::shape_pattern => specs = parse_shape_specs(inner)?,
+            _ => {}
+        }
+    }
+
+    let group = Shape::Group(specs);
+    if is_any {
+        apply_any(group)
+    } else {
+        Ok(group)
+    }
+}
+
+/// Turn a shape into its "any" (every suit permutation) form
+fn apply_any(shape: Shape) -> Result<Shape, ParseError> {
+    match shape {
+        Shape::Exact(p) | Shape::AnyDistribution(p) => Ok(Shape::AnyDistribution(p)),
+        Shape::Wildcard(p) | Shape::AnyWildcard(p) => Ok(Shape::AnyWildcard(p)),
+        Shape::Range(_) | Shape::Constraint { .. } => Err(ParseError {
+            message: "\"any\" cannot be applied to a range shape".to_string(),
+        }),
+        Shape::Group(specs) => Ok(Shape::Group(
+            specs
+                .into_iter()
+                .map(|spec| {
+                    Ok(ShapeSpec {
+                        include: spec.include,
+                        shape: apply_any(spec.shape)?,
+                    })
+                })
+                .collect::<Result<_, ParseError>>()?,
+        )),
+    }
+}
+
 /// Parse a shape specification like "any 4333" or "54xx"
 fn parse_shape_spec(pair: Pair<Rule>) -> Result<Shape, ParseError> {
     let mut is_any = false;
@@ -790,6 +900,7 @@ fn parse_shape_spec(pair: Pair<Rule>) -> Result<Shape, ParseError> {
     for inner in pair.into_inner() {
         match inner.as_rule() {
             Rule::shape_any => is_any = true,
+            Rule::shape_group => return parse_shape_group(inner),
             Rule::shape_digits_any => digits_str = inner.as_str(),
             Rule::shape_digits_marked => {
                 digits_str = inner.as_str();
@@ -870,6 +981,74 @@ fn parse_shape_spec(pair: Pair<Rule>) -> Result<Shape, ParseError> {
     }
 }
 
+/// Parse a range shape like "5+ 4+ x x" or "2-4 x x x" into per-suit (min, max) bounds,
+/// or a constraint shape when it has "anyN+" elements ("5+ any4+ x x")
+fn parse_shape_range(pair: Pair<Rule>) -> Result<Shape, ParseError> {
+    let mut ranges = [(None, None); 4];
+    let mut any_other = [None; 4];
+    let mut count = 0;
+
+    for elem in pair.into_inner() {
+        if count >= 4 {
+            return Err(ParseError {
+                message: "Range shape must have exactly 4 suit lengths".to_string(),
+            });
+        }
+        let text = elem.as_str();
+        ranges[count] = match elem.as_rule() {
+            Rule::shape_range_any => (None, None),
+            Rule::shape_range_other => {
+                any_other[count] = Some(parse_suit_length(text[3..].trim_end_matches('+'))?);
+                (None, None)
+            }
+            Rule::shape_range_exact => {
+                let len = parse_suit_length(text)?;
+                (Some(len), Some(len))
+            }
+            Rule::shape_range_bound => match text.split_once('-') {
+                Some((min, max)) => {
+                    let (min, max) = (parse_suit_length(min)?, parse_suit_length(max)?);
+                    if min > max {
+                        return Err(ParseError {
+                            message: format!("Invalid shape range {}: min exceeds max", text),
+                        });
+                    }
+                    (Some(min), Some(max))
+                }
+                None => (Some(parse_suit_length(text.trim_end_matches('+'))?), None),
+            },
+            _ => {
+                return Err(ParseError {
+                    message: format!("Unexpected element in range shape: {}", text),
+                })
+            }
+        };
+        count += 1;
+    }
+
+    if count != 4 {
+        return Err(ParseError {
+            message: "Range shape must have exactly 4 suit lengths".to_string(),
+        });
+    }
+
+    if any_other.iter().any(Option::is_some) {
+        Ok(Shape::Constraint { ranges, any_other })
+    } else {
+        Ok(Shape::Range(ranges))
+    }
+}
+
+/// Parse a suit length (0-13) in a range shape
+fn parse_suit_length(s: &str) -> Result<u8, ParseError> {
+    match s.parse::<u8>() {
+        Ok(len) if len <= 13 => Ok(len),
+        _ => Err(ParseError {
+            message: format!("Shape length {} is out of range (max 13)", s),
+        }),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -948,6 +1127,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_double_negation() {
+        let ast = parse("!!x").unwrap();
+        let inner = Expr::UnaryOp {
+            op: UnaryOp::Not,
+            expr: Box::new(Expr::Variable("x".to_string())),
+        };
+        assert_eq!(
+            ast,
+            Expr::UnaryOp {
+                op: UnaryOp::Not,
+                expr: Box::new(inner),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_not_precedence() {
+        // !a && b parses as (!a) && b
+        let ast = parse("!a && b").unwrap();
+        match ast {
+            Expr::BinaryOp {
+                op: BinaryOp::And,
+                left,
+                right,
+            } => {
+                assert!(matches!(
+                    *left,
+                    Expr::UnaryOp {
+                        op: UnaryOp::Not,
+                        ..
+                    }
+                ));
+                assert_eq!(*right, Expr::Variable("b".to_string()));
+            }
+            _ => panic!("Expected AND with negated left operand"),
+        }
+    }
+
     #[test]
     fn test_parse_not_keyword() {
         // Test not keyword
@@ -976,6 +1194,180 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_program_condition_statement() {
+        let program = parse_program("condition hcp(north) >= 12").unwrap();
+        assert_eq!(program.statements.len(), 1);
+        match &program.statements[0] {
+            Statement::Condition(Expr::BinaryOp { op, left, right }) => {
+                assert_eq!(*op, BinaryOp::Ge);
+                assert!(matches!(
+                    **left,
+                    Expr::FunctionCall {
+                        func: Function::Hcp,
+                        ..
+                    }
+                ));
+                assert_eq!(**right, Expr::Literal(12));
+            }
+            _ => panic!("Expected condition statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_printcompact_with_value() {
+        let program = parse_program("action printcompact hcp(north)").unwrap();
+        assert_eq!(program.statements.len(), 1);
+        match &program.statements[0] {
+            Statement::Action {
+                format: Some(ActionType::PrintCompact(Some(expr))),
+                ..
+            } => {
+                assert!(matches!(
+                    expr,
+                    Expr::FunctionCall {
+                        func: Function::Hcp,
+                        ..
+                    }
+                ));
+            }
+            _ => panic!("Expected printcompact action with a value"),
+        }
+    }
+
+    #[test]
+    fn test_parse_printcompact_bare() {
+        // The next statement must not be taken as the printcompact value
+        let program =
+            parse_program("action printcompact\nproduce 5\nx = hcp(north)\ncondition x > 10")
+                .unwrap();
+        assert_eq!(program.statements.len(), 4);
+        assert!(matches!(
+            &program.statements[0],
+            Statement::Action {
+                format: Some(ActionType::PrintCompact(None)),
+                ..
+            }
+        ));
+        assert_eq!(program.statements[1], Statement::Produce(5));
+
+        let program = parse_program("printcompact").unwrap();
+        assert!(matches!(
+            &program.statements[0],
+            Statement::Action {
+                format: Some(ActionType::PrintCompact(None)),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_parse_program_with_comments() {
+        let commented = parse_program(
+            "# opening bids\n\
+             produce 10 # deals to print\n\
+             /* north opens\n   one notrump */\n\
+             nt = hcp(north) >= 15 // strong\n\
+             condition nt and /* inline */ shape(north, any 4333 + any 4432)\n\
+             action average \"# not a comment\" hcp(south)\n",
+        )
+        .unwrap();
+        let plain = parse_program(
+            "produce 10\n\
+             nt = hcp(north) >= 15\n\
+             condition nt and shape(north, any 4333 + any 4432)\n\
+             action average \"# not a comment\" hcp(south)\n",
+        )
+        .unwrap();
+        assert_eq!(commented, plain);
+        assert_eq!(commented.statements.len(), 4);
+        assert!(matches!(
+            &commented.statements[3],
+            Statement::Action { averages, .. }
+                if averages[0].label.as_deref() == Some("# not a comment")
+        ));
+    }
+
+    #[test]
+    fn test_parse_printf_template() {
+        let program =
+            parse_program("action printf \"N:%{hcp(north)} S:%{hcp(south)} 100%%\"\nproduce 5")
+                .unwrap();
+        assert_eq!(program.statements.len(), 2);
+        match &program.statements[0] {
+            Statement::Action {
+                format: Some(ActionType::PrintF(parts)),
+                ..
+            } => {
+                let hcp = |position| Expr::FunctionCall {
+                    func: Function::Hcp,
+                    args: vec![Expr::Position(position)],
+                };
+                assert_eq!(
+                    parts,
+                    &vec![
+                        TemplatePart::Text("N:".to_string()),
+                        TemplatePart::Expr(hcp(Position::North)),
+                        TemplatePart::Text(" S:".to_string()),
+                        TemplatePart::Expr(hcp(Position::South)),
+                        TemplatePart::Text(" 100%".to_string()),
+                    ]
+                );
+            }
+            _ => panic!("Expected printf action"),
+        }
+
+        // Standalone form
+        assert!(matches!(
+            &parse_program("printf \"%{hcp(east)}\"").unwrap().statements[0],
+            Statement::Action {
+                format: Some(ActionType::PrintF(_)),
+                ..
+            }
+        ));
+
+        // Shape patterns work inside a substitution
+        let program = parse_program("printf \"%{shape(north, 5332)}\"").unwrap();
+        let shape = parse(&preprocess("shape(north, 5332)")).unwrap();
+        assert!(matches!(
+            &program.statements[0],
+            Statement::Action {
+                format: Some(ActionType::PrintF(parts)),
+                ..
+            } if parts == &vec![TemplatePart::Expr(shape)]
+        ));
+
+        // Bad escapes and expressions are rejected
+        assert!(parse_program("action printf \"%d\"").is_err());
+        assert!(parse_program("action printf \"%{hcp(north)\"").is_err());
+        assert!(parse_program("action printf \"%{hcp(}\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_frequency_forms() {
+        let expected = FrequencySpec {
+            label: Some("HCP".to_string()),
+            expr: parse("hcp(north)").unwrap(),
+            range: Some((0, 37)),
+        };
+
+        for input in [
+            "frequency \"HCP\" hcp(north) 0 37",
+            "frequency \"HCP\" (hcp(north), 0, 37)",
+            "action frequency \"HCP\" hcp(north) 0 37",
+            "action frequency \"HCP\" (hcp(north), 0, 37)",
+        ] {
+            let program = parse_program(input).unwrap();
+            assert_eq!(program.statements.len(), 1, "{}", input);
+            match &program.statements[0] {
+                Statement::Action { frequencies, .. } => {
+                    assert_eq!(frequencies, &vec![expected.clone()], "{}", input);
+                }
+                _ => panic!("Expected frequency action for {}", input),
+            }
+        }
+    }
+
     #[test]
     fn test_parse_program_with_assignment() {
         let program = parse_program("opener = hcp(north) >= 15\nopener").unwrap();
@@ -1072,6 +1464,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_ternary_precedence() {
+        // Ternary binds looser than || and &&
+        assert_eq!(
+            parse("hcp(north) > 20 || hcp(south) > 20 ? 2 : 1").unwrap(),
+            parse("(hcp(north) > 20 || hcp(south) > 20) ? 2 : 1").unwrap()
+        );
+        // ...and looser than arithmetic in the branches
+        assert_eq!(
+            parse("hcp(north) >= 15 ? hcp(north) + 1 : hcp(north) * 2").unwrap(),
+            parse("(hcp(north) >= 15) ? (hcp(north) + 1) : (hcp(north) * 2)").unwrap()
+        );
+        // The else branch chains to the right without parentheses
+        assert_eq!(
+            parse("hcp(north) >= 20 ? 3 : hcp(north) >= 15 ? 2 : 1").unwrap(),
+            parse("hcp(north) >= 20 ? 3 : (hcp(north) >= 15 ? 2 : 1)").unwrap()
+        );
+    }
+
     #[test]
     fn test_parse_nested_ternary() {
         // Nested ternary: hcp(north) >= 15 ? (hearts(north) >= 5 ? 2 : 1) : 0
@@ -1088,6 +1499,186 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_lowercase_card() {
+        assert_eq!(
+            parse("hascard(north, as)").unwrap(),
+            parse("hascard(north, AS)").unwrap()
+        );
+        assert_eq!(
+            parse("hascard(north, tc)").unwrap(),
+            parse("hascard(north, TC)").unwrap()
+        );
+        assert_eq!(
+            parse("hasanycard(north, kh, qd)").unwrap(),
+            parse("hasanycard(north, KH, QD)").unwrap()
+        );
+
+        // Identifiers that merely start like a card are still variables
+        assert_eq!(parse("ace").unwrap(), Expr::Variable("ace".to_string()));
+    }
+
+    #[test]
+    fn test_lowercase_card_names_stay_variables() {
+        assert_eq!(parse("kh").unwrap(), Expr::Variable("kh".to_string()));
+
+        let program = parse_program(
+            "th = hcp(north) >= 10
+as = aces(north)
+th && as > 1",
+        )
+        .unwrap();
+        match &program.statements[2] {
+            Statement::Expression(Expr::BinaryOp { left, right, .. }) => {
+                assert_eq!(**left, Expr::Variable("th".to_string()));
+                match &**right {
+                    Expr::BinaryOp { left, .. } => {
+                        assert_eq!(**left, Expr::Variable("as".to_string()))
+                    }
+                    other => panic!("Expected comparison, got {:?}", other),
+                }
+            }
+            other => panic!("Expected binary expression, got {:?}", other),
+        }
+    }
+
+    /// Extract the single shape of a shape(...) call
+    fn shape_arg(input: &str) -> Shape {
+        match parse(input).unwrap() {
+            Expr::FunctionCall { args, .. } => match &args[1] {
+                Expr::ShapePattern(p) => p.specs[0].shape.clone(),
+                other => panic!("Expected shape pattern, got {:?}", other),
+            },
+            other => panic!("Expected function call, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_range_shape() {
+        assert_eq!(
+            shape_arg("shape(north, 5+ 4+ x x)"),
+            Shape::Range([(Some(5), None), (Some(4), None), (None, None), (None, None)])
+        );
+        assert_eq!(
+            shape_arg("shape(south, 2-4 x 3 x)"),
+            Shape::Range([
+                (Some(2), Some(4)),
+                (None, None),
+                (Some(3), Some(3)),
+                (None, None)
+            ])
+        );
+
+        // Range shapes combine with other specs
+        match parse("shape(north, 5+ x x x + any 4333)").unwrap() {
+            Expr::FunctionCall { args, .. } => match &args[1] {
+                Expr::ShapePattern(p) => assert_eq!(p.specs.len(), 2),
+                _ => panic!("Expected shape pattern"),
+            },
+            _ => panic!("Expected function call"),
+        }
+
+        // Invalid ranges
+        assert!(parse("shape(north, 4-2 x x x)").is_err());
+        assert!(parse("shape(north, 14+ x x x)").is_err());
+    }
+
+    #[test]
+    fn test_parse_constraint_shape() {
+        assert_eq!(
+            shape_arg("shape(north, 5+ any4+ x x)"),
+            Shape::Constraint {
+                ranges: [(Some(5), None), (None, None), (None, None), (None, None)],
+                any_other: [None, Some(4), None, None],
+            }
+        );
+
+        // 5-4-2-2 has another four-card suit, 5-3-3-2 does not
+        assert!(shape_matches("shape(north, 5+ any4+ x x)", [5, 4, 2, 2]));
+        assert!(shape_matches("shape(north, 5+ any4+ x x)", [5, 2, 2, 4]));
+        assert!(!shape_matches("shape(north, 5+ any4+ x x)", [5, 3, 3, 2]));
+        assert!(!shape_matches("shape(north, 5+ any4+ x x)", [4, 5, 2, 2]));
+
+        // Combines with other specs; "any" cannot be applied to it
+        assert!(shape_matches(
+            "shape(north, 5+ any4+ x x + any 5332)",
+            [3, 5, 3, 2]
+        ));
+        assert!(parse("shape(north, any(5+ any4+ x x))").is_err());
+        assert!(parse("shape(north, 5+ any14+ x x)").is_err());
+    }
+
+    /// Whether a shape(north, ...) pattern matches the S-H-D-C lengths
+    fn shape_matches(input: &str, lengths: [usize; 4]) -> bool {
+        let index = dealer_core::shape_to_index(lengths[0], lengths[1], lengths[2], lengths[3]);
+        match parse(&crate::preprocess(input)).unwrap() {
+            Expr::FunctionCall { args, .. } => match &args[1] {
+                Expr::ShapePattern(p) => p.matches_index(index),
+                other => panic!("Expected shape pattern, got {:?}", other),
+            },
+            other => panic!("Expected function call, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_shape_groups() {
+        // "any" applies to every member of the group
+        let input = "shape(north, any(4333 + 4432) - 4432)";
+        match parse(&crate::preprocess(input)).unwrap() {
+            Expr::FunctionCall { args, .. } => match &args[1] {
+                Expr::ShapePattern(p) => {
+                    assert_eq!(
+                        p.specs[0].shape,
+                        Shape::Group(vec![
+                            ShapeSpec {
+                                include: true,
+                                shape: Shape::AnyDistribution([4, 3, 3, 3]),
+                            },
+                            ShapeSpec {
+                                include: true,
+                                shape: Shape::AnyDistribution([4, 4, 3, 2]),
+                            },
+                        ])
+                    );
+                    assert!(!p.specs[1].include);
+                    assert_eq!(p.specs[1].shape, Shape::Exact([4, 4, 3, 2]));
+                }
+                other => panic!("Expected shape pattern, got {:?}", other),
+            },
+            other => panic!("Expected function call, got {:?}", other),
+        }
+        assert!(shape_matches(input, [3, 3, 4, 3]));
+        assert!(shape_matches(input, [2, 4, 3, 4]));
+        assert!(!shape_matches(input, [4, 4, 3, 2]));
+        assert!(!shape_matches(input, [5, 3, 3, 2]));
+
+        // Without grouping, specs combine left to right
+        let flat = "shape(north, any 4432 - 4432 + 44xx)";
+        assert!(shape_matches(flat, [4, 4, 3, 2]));
+        // Grouping overrides that: the whole group is excluded
+        let grouped = "shape(north, any 4432 - (4432 + 44xx))";
+        assert!(!shape_matches(grouped, [4, 4, 3, 2]));
+        assert!(shape_matches(grouped, [4, 3, 4, 2]));
+
+        // A leading plain group, and nesting
+        assert!(shape_matches(
+            "shape(north, (any 4333 + any 5332) - (4333 + (3433)))",
+            [3, 3, 4, 3]
+        ));
+        assert!(!shape_matches(
+            "shape(north, (any 4333 + any 5332) - (4333 + (3433)))",
+            [3, 4, 3, 3]
+        ));
+
+        // "any" has no meaning for a range shape
+        assert!(parse("shape(north, any(5+ x x x))").is_err());
+        // Ordinary parenthesized expressions are unaffected
+        assert_eq!(
+            parse("(hcp(north) + 1) * 2").unwrap(),
+            parse("((hcp(north)) + 1) * 2").unwrap()
+        );
+    }
+
     #[test]
     fn test_parse_predeal_with_suit_only() {
         // Predeal with suit-only holdings (no specific cards for that suit)
@@ -1127,6 +1718,29 @@ mod tests {
         assert_eq!(cards.len(), 8);
     }
 
+    #[test]
+    fn test_parse_predeal_dotted_holdings() {
+        // PBN-style dots separate holdings just like commas
+        let dotted = parse_program("predeal south SAK.HQ.D.CAKQJT").unwrap();
+        let commas = parse_program("predeal south SAK,HQ,D,CAKQJT").unwrap();
+        assert_eq!(dotted.statements, commas.statements);
+
+        let program = parse_program("predeal west SAK.H.D.C").unwrap();
+        match &program.statements[0] {
+            Statement::Predeal { position, cards } => {
+                assert_eq!(*position, Position::West);
+                assert_eq!(
+                    cards,
+                    &vec![
+                        dealer_core::Card::new(dealer_core::Suit::Spades, dealer_core::Rank::Ace),
+                        dealer_core::Card::new(dealer_core::Suit::Spades, dealer_core::Rank::King)
+                    ]
+                );
+            }
+            other => panic!("Expected predeal, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_parse_chained_comparison() {
         // Chained comparison: a==b==c becomes (a==b) && (b==c)
@@ -1262,4 +1876,76 @@ mod tests {
             "Should NOT have Expression(Literal(1000000))"
         );
     }
+
+    #[test]
+    fn test_display_round_trip() {
+        let sources = [
+            "hcp(north) >= 15 && shape(north, 5332)",
+            "hcp(north) + hcp(south) >= 25 || spades(north) >= 5 && hearts(south) < 3",
+            "(hcp(north) + 2) * 3 - hcp(south) / 2 % 4 > 10",
+            "10 - (4 - 3) == 9 - 1",
+            "not (hcp(east) > 10 or hcp(west) > 10)",
+            "-(hcp(north) - 20) >= -5",
+            "hcp(ns) >= 25 ? tricks(south, notrump) : 0",
+            "a ? (b ? 1 : 2) : (c ? 3 : 4)",
+            "(hcp(north) > 10) == (hcp(south) > 10)",
+            "10 <= hcp(north) <= 12",
+            "hascard(west, AS) && !hascard(west, KH)",
+            "shape(north, any 4333 + 5xxx - 5431 + any (6xxx + 55xx))",
+            "shape(south, 5+ 4+ x x) && shape(east, 2-4 x x x)",
+            "shape(west, 3-3 x x x)",
+            "shape(north, 5+ any4+ x x) || shape(south, 4 any5+ any4+ x)",
+            "ddscore(south, hearts, 4, 1) > score(1, 34, tricks(south, notrump))",
+        ];
+        for source in sources {
+            let ast = parse(&preprocess(source)).unwrap();
+            let printed = ast.to_string();
+            let reparsed = parse(&preprocess(&printed))
+                .unwrap_or_else(|e| panic!("{:?} printed as {:?}: {:?}", source, printed, e));
+            assert_eq!(ast, reparsed, "{:?} printed as {:?}", source, printed);
+        }
+    }
+
+    #[test]
+    fn test_display_canonical_form() {
+        let cases = [
+            (
+                "hcp(north)>=15 and shape(north,5332)",
+                "hcp(north) >= 15 && shape(north, 5332)",
+            ),
+            ("(1 + 2) + 3", "1 + 2 + 3"),
+            ("1 + (2 + 3)", "1 + (2 + 3)"),
+            ("((a || b) && c)", "(a || b) && c"),
+            ("not (a)", "!a"),
+            ("shape(south, 5+ 3 x x)", "shape(south, 5+ 3 x x)"),
+        ];
+        for (source, expected) in cases {
+            let ast = parse(&preprocess(source)).unwrap();
+            assert_eq!(ast.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_rotate() {
+        let program = parse_program("rotate off\nhcp(north) >= 15\nproduce 5\n").unwrap();
+        assert_eq!(program.statements[0], Statement::Rotate(false));
+        assert!(matches!(program.statements[1], Statement::Expression(_)));
+
+        let program = parse_program("rotate ON").unwrap();
+        assert_eq!(program.statements, vec![Statement::Rotate(true)]);
+    }
+
+    #[test]
+    fn test_parse_evalcontract() {
+        // Standalone, and after an action list (the comma separates statements)
+        for input in [
+            "hcp(north) >= 15\nevalcontract\n",
+            "hcp(north) >= 15\naction printoneline, evalcontract\n",
+            "hcp(north) >= 15\naction printcompact\nevalcontract\n",
+        ] {
+            let program = parse_program(input).unwrap();
+            assert_eq!(program.statements.last(), Some(&Statement::EvalContract));
+            assert!(matches!(program.statements[0], Statement::Expression(_)));
+        }
+    }
 }
@@ -14,33 +14,91 @@ pub fn preprocess(input: &str) -> String {
     // 2. Are NOT already marked with %s
     // 3. Are standalone (not part of wildcards like 54xx)
 
-    let shape_re = Regex::new(r"shape\s*\([^)]+\)").unwrap();
-
-    shape_re
-        .replace_all(input, |caps: &regex::Captures| {
-            let shape_call = &caps[0];
-
-            // Match 4-digit numbers, capturing position info
-            let digit_re = Regex::new(r"\b(\d{4})\b").unwrap();
-
-            digit_re
-                .replace_all(shape_call, |inner_caps: &regex::Captures| {
-                    let digits = &inner_caps[1];
-                    let match_start = inner_caps.get(0).unwrap().start();
-
-                    // Check if this 4-digit number follows "any "
-                    let before_match = &shape_call[..match_start];
-                    if before_match.ends_with("any ") {
-                        // Don't mark it - "any" disambiguates
-                        digits.to_string()
-                    } else {
-                        // Mark it with %s prefix
-                        format!("%s{}", digits)
+    let shape_re = Regex::new(r"shape\s*\(").unwrap();
+    let digit_re = Regex::new(r"\b(\d{4})\b").unwrap();
+
+    let mut result = String::with_capacity(input.len());
+    let mut copied = 0;
+    let skipped = comment_and_string_spans(input);
+
+    for m in shape_re.find_iter(input) {
+        if m.start() < copied {
+            continue; // Inside a shape() call already handled
+        }
+        if skipped
+            .iter()
+            .any(|&(start, end)| (start..end).contains(&m.start()))
+        {
+            continue; // "shape(" in a comment or label is just text
+        }
+
+        // Find the matching close paren, so grouped shapes like
+        // shape(north, any(4333 + 4432) - 4432) are handled as a whole
+        let mut depth = 0;
+        let mut end = input.len();
+        for (i, c) in input[m.end() - 1..].char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = m.end() + i;
+                        break;
                     }
-                })
-                .to_string()
-        })
-        .to_string()
+                }
+                _ => {}
+            }
+        }
+
+        let shape_call = &input[m.start()..end];
+        result.push_str(&input[copied..m.start()]);
+        result.push_str(
+            &digit_re.replace_all(shape_call, |inner_caps: &regex::Captures| {
+                let digits = &inner_caps[1];
+                let match_start = inner_caps.get(0).unwrap().start();
+
+                // Check if this 4-digit number follows "any "
+                let before_match = &shape_call[..match_start];
+                if before_match.ends_with("any ") {
+                    // Don't mark it - "any" disambiguates
+                    digits.to_string()
+                } else {
+                    // Mark it with %s prefix
+                    format!("%s{}", digits)
+                }
+            }),
+        );
+        copied = end;
+    }
+
+    result.push_str(&input[copied..]);
+    result
+}
+
+/// Byte ranges of comments (#, //, /* */) and string literals, which the
+/// grammar skips or keeps verbatim
+fn comment_and_string_spans(input: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < input.len() {
+        let rest = &input[i..];
+        let len = if rest.starts_with('#') || rest.starts_with("//") {
+            rest.find('\n').unwrap_or(rest.len())
+        } else if let Some(body) = rest.strip_prefix("/*") {
+            body.find("*/").map_or(rest.len(), |end| end + 4)
+        } else if let Some(body) = rest.strip_prefix('"') {
+            body.find('"').map_or(rest.len(), |end| end + 2)
+        } else {
+            0
+        };
+        if len > 0 {
+            spans.push((i, i + len));
+            i += len;
+        } else {
+            i += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    spans
 }
 
 #[cfg(test)]
@@ -99,4 +157,25 @@ mod tests {
             "shape(north, any 4333 + %s5242 - %s4441)"
         );
     }
+
+    #[test]
+    fn test_preprocess_shape_groups() {
+        assert_eq!(
+            preprocess("shape(north, any(4333 + 4432) - 4432) && hcp(north) > 1000"),
+            "shape(north, any(%s4333 + %s4432) - %s4432) && hcp(north) > 1000"
+        );
+    }
+
+    #[test]
+    fn test_preprocess_skips_comments_and_strings() {
+        // An unclosed "shape(" in a comment must not swallow the rest of the script
+        assert_eq!(
+            preprocess("# count shape( hands\nproduce 1000\nshape(north, 5332)"),
+            "# count shape( hands\nproduce 1000\nshape(north, %s5332)"
+        );
+        assert_eq!(
+            preprocess("/* shape(north, 5431) */ average \"shape(1000\" hcp(north)"),
+            "/* shape(north, 5431) */ average \"shape(1000\" hcp(north)"
+        );
+    }
 }
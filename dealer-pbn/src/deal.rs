@@ -1,4 +1,4 @@
-use dealer_core::{Card, Deal, Hand, Position, Rank, Suit};
+use dealer_core::{Card, Deal, Hand, Position, PositionExt, Rank, RankExt, Suit, SuitExt};
 
 /// Error type for PBN parsing
 #[derive(Debug, Clone)]
@@ -21,6 +21,69 @@ pub struct PbnDeal {
     pub deal: Deal,
 }
 
+impl PbnDeal {
+    /// Convert to a complete Deal, checking that every hand has 13 cards
+    /// and that no card appears twice
+    pub fn to_deal(&self) -> Result<Deal, ParseError> {
+        let mut seen = 0u64;
+        for pos in Position::ALL {
+            let hand = self.deal.hand(pos);
+            if hand.len() != 13 {
+                return Err(ParseError {
+                    message: format!(
+                        "Hand {} has {} cards, expected 13",
+                        position_char(pos),
+                        hand.len()
+                    ),
+                });
+            }
+            let mask = hand.card_mask();
+            if seen & mask != 0 || mask.count_ones() != 13 {
+                return Err(ParseError {
+                    message: format!("Duplicate card in hand {}", position_char(pos)),
+                });
+            }
+            seen |= mask;
+        }
+        Ok(self.deal.clone())
+    }
+
+    /// Convert to a deal for double-dummy analysis, also accepting endings:
+    /// every hand must hold the same number of cards (at least one) and no
+    /// card may appear twice
+    pub fn to_ending(&self) -> Result<Deal, ParseError> {
+        let length = self.deal.hand(Position::North).len();
+        if length == 0 {
+            return Err(ParseError {
+                message: "Hand N has no cards".to_string(),
+            });
+        }
+
+        let mut seen = 0u64;
+        for pos in Position::ALL {
+            let hand = self.deal.hand(pos);
+            if hand.len() != length {
+                return Err(ParseError {
+                    message: format!(
+                        "Hand {} has {} cards, expected {} like hand N",
+                        position_char(pos),
+                        hand.len(),
+                        length
+                    ),
+                });
+            }
+            let mask = hand.card_mask();
+            if seen & mask != 0 || mask.count_ones() as usize != length {
+                return Err(ParseError {
+                    message: format!("Duplicate card in hand {}", position_char(pos)),
+                });
+            }
+            seen |= mask;
+        }
+        Ok(self.deal.clone())
+    }
+}
+
 /// Parse a PBN [Deal "..."] tag
 /// Format: [Deal "N:KQ4.QJ982..AKQ43 J653.A73.985.J97 9.K54.KQT732.652 AT872.T6.AJ64.T8"]
 /// Position:Spades.Hearts.Diamonds.Clubs for each hand (clockwise from position)
@@ -80,23 +143,7 @@ pub fn parse_deal_tag(input: &str) -> Result<PbnDeal, ParseError> {
 
 /// Format a Deal as a PBN [Deal "..."] tag
 pub fn format_deal_tag(deal: &Deal, first_seat: Position) -> String {
-    let mut result = String::from("[Deal \"");
-
-    // Add position
-    result.push(position_char(first_seat));
-    result.push(':');
-
-    // Add hands in clockwise order from first_seat
-    for i in 0..4 {
-        if i > 0 {
-            result.push(' ');
-        }
-        let pos = rotate_position(first_seat, i);
-        result.push_str(&format_hand(deal.hand(pos)));
-    }
-
-    result.push_str("\"]");
-    result
+    format!("[Deal \"{}\"]", deal.to_pbn_string(first_seat))
 }
 
 /// Parse a position character
@@ -124,20 +171,17 @@ fn position_char(pos: Position) -> char {
 
 /// Rotate position clockwise by n steps
 fn rotate_position(start: Position, steps: usize) -> Position {
-    let positions = [
-        Position::North,
-        Position::East,
-        Position::South,
-        Position::West,
-    ];
-    let start_idx = positions.iter().position(|&p| p == start).unwrap();
-    let new_idx = (start_idx + steps) % 4;
-    positions[new_idx]
+    start.rotate(steps)
 }
 
 /// Parse a hand in PBN format: Spades.Hearts.Diamonds.Clubs
-/// Example: "KQ4.QJ982..AKQ43" (void diamond suit shown as empty)
+/// Example: "KQ4.QJ982..AKQ43" (void diamond suit shown as empty or "-")
+/// A hand of just "-" is unknown and parses as an empty hand
 fn parse_hand(s: &str) -> Result<Hand, ParseError> {
+    if s == "-" {
+        return Ok(Hand::new());
+    }
+
     let suits_str: Vec<&str> = s.split('.').collect();
     if suits_str.len() != 4 {
         return Err(ParseError {
@@ -149,19 +193,24 @@ fn parse_hand(s: &str) -> Result<Hand, ParseError> {
     }
 
     let mut hand = Hand::new();
-    let suits = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+    let suits = Suit::DESCENDING;
 
     for (suit_idx, &suit_str) in suits_str.iter().enumerate() {
         let suit = suits[suit_idx];
 
-        // Empty string means void suit
-        if suit_str.is_empty() {
+        // Empty string or "-" means void suit
+        if suit_str.is_empty() || suit_str == "-" {
             continue;
         }
 
         // Parse each card rank in the suit
         for c in suit_str.chars() {
             let rank = parse_rank(c)?;
+            if hand.len() == 13 {
+                return Err(ParseError {
+                    message: format!("Hand {} has more than 13 cards", s),
+                });
+            }
             hand.add_card(Card::new(suit, rank));
         }
     }
@@ -169,67 +218,11 @@ fn parse_hand(s: &str) -> Result<Hand, ParseError> {
     Ok(hand)
 }
 
-/// Format a hand in PBN format
-fn format_hand(hand: &Hand) -> String {
-    let suits = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
-    let mut result = Vec::new();
-
-    for &suit in &suits {
-        let cards = hand.cards_in_suit(suit);
-        if cards.is_empty() {
-            result.push(String::new());
-        } else {
-            // Sort by rank descending (Ace first)
-            let mut cards = cards;
-            cards.sort_by(|a, b| b.rank.cmp(&a.rank));
-
-            let suit_str: String = cards.iter().map(|c| rank_char(c.rank)).collect();
-            result.push(suit_str);
-        }
-    }
-
-    result.join(".")
-}
-
 /// Parse a rank character
 fn parse_rank(c: char) -> Result<Rank, ParseError> {
-    match c.to_uppercase().next().unwrap() {
-        'A' => Ok(Rank::Ace),
-        'K' => Ok(Rank::King),
-        'Q' => Ok(Rank::Queen),
-        'J' => Ok(Rank::Jack),
-        'T' => Ok(Rank::Ten),
-        '9' => Ok(Rank::Nine),
-        '8' => Ok(Rank::Eight),
-        '7' => Ok(Rank::Seven),
-        '6' => Ok(Rank::Six),
-        '5' => Ok(Rank::Five),
-        '4' => Ok(Rank::Four),
-        '3' => Ok(Rank::Three),
-        '2' => Ok(Rank::Two),
-        _ => Err(ParseError {
-            message: format!("Invalid rank character: {}", c),
-        }),
-    }
-}
-
-/// Get rank character (uppercase)
-fn rank_char(rank: Rank) -> char {
-    match rank {
-        Rank::Ace => 'A',
-        Rank::King => 'K',
-        Rank::Queen => 'Q',
-        Rank::Jack => 'J',
-        Rank::Ten => 'T',
-        Rank::Nine => '9',
-        Rank::Eight => '8',
-        Rank::Seven => '7',
-        Rank::Six => '6',
-        Rank::Five => '5',
-        Rank::Four => '4',
-        Rank::Three => '3',
-        Rank::Two => '2',
-    }
+    Rank::from_char(c).ok_or_else(|| ParseError {
+        message: format!("Invalid rank character: {}", c),
+    })
 }
 
 #[cfg(test)]
@@ -254,6 +247,119 @@ mod tests {
         assert_eq!(north.suit_length(Suit::Clubs), 5);
     }
 
+    #[test]
+    fn test_to_pbn_string_round_trip() {
+        let deal = dealer_core::DealGenerator::new(1).generate();
+        for leader in Position::ALL {
+            let body = deal.to_pbn_string(leader);
+            let parsed = parse_deal_tag(&format!("[Deal \"{}\"]", body)).unwrap();
+            assert_eq!(parsed.first_seat, leader);
+            let round_trip = parsed.to_deal().unwrap();
+            for pos in Position::ALL {
+                assert_eq!(round_trip.hand(pos).card_mask(), deal.hand(pos).card_mask());
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_deal_hcp() {
+        let input =
+            r#"[Deal "N:KQ4.QJ982..AKQ43 J653.A73.985.J97 9.K54.KQT732.652 AT872.T6.AJ64.T8"]"#;
+
+        let deal = parse_deal_tag(input).unwrap().to_deal().unwrap();
+        assert_eq!(deal.hand(Position::North).hcp(), 17);
+        assert_eq!(deal.hand(Position::East).hcp(), 6);
+        assert_eq!(deal.hand(Position::South).hcp(), 8);
+        assert_eq!(deal.hand(Position::West).hcp(), 9);
+    }
+
+    #[test]
+    fn test_to_deal_honors_first_seat() {
+        // Same hands as above, listed starting from East
+        let input =
+            r#"[Deal "E:J653.A73.985.J97 9.K54.KQT732.652 AT872.T6.AJ64.T8 KQ4.QJ982..AKQ43"]"#;
+
+        let deal = parse_deal_tag(input).unwrap().to_deal().unwrap();
+        assert_eq!(deal.hand(Position::North).hcp(), 17);
+        assert_eq!(deal.hand(Position::East).hcp(), 6);
+        assert_eq!(deal.hand(Position::South).hcp(), 8);
+        assert_eq!(deal.hand(Position::West).hcp(), 9);
+    }
+
+    #[test]
+    fn test_to_deal_dash_void() {
+        let input =
+            r#"[Deal "N:KQ4.QJ982.-.AKQ43 J653.A73.985.J97 9.K54.KQT732.652 AT872.T6.AJ64.T8"]"#;
+
+        let deal = parse_deal_tag(input).unwrap().to_deal().unwrap();
+        assert_eq!(deal.hand(Position::North).suit_length(Suit::Diamonds), 0);
+        assert_eq!(deal.hand(Position::North).len(), 13);
+    }
+
+    #[test]
+    fn test_to_deal_rejects_incomplete_hands() {
+        // North is missing a card
+        let input =
+            r#"[Deal "N:KQ.QJ982..AKQ43 J653.A73.985.J97 9.K54.KQT732.652 AT872.T6.AJ64.T8"]"#;
+        let err = parse_deal_tag(input).unwrap().to_deal().unwrap_err();
+        assert!(err.message.contains("Hand N has 12 cards"));
+
+        // Unknown hand
+        let input = r#"[Deal "N:KQ4.QJ982..AKQ43 - 9.K54.KQT732.652 AT872.T6.AJ64.T8"]"#;
+        let err = parse_deal_tag(input).unwrap().to_deal().unwrap_err();
+        assert!(err.message.contains("Hand E has 0 cards"));
+
+        // Duplicate card: East holds North's spade king instead of the jack
+        let input =
+            r#"[Deal "N:KQ4.QJ982..AKQ43 K653.A73.985.J97 9.K54.KQT732.652 AT872.T6.AJ64.T8"]"#;
+        let err = parse_deal_tag(input).unwrap().to_deal().unwrap_err();
+        assert!(err.message.contains("Duplicate card"));
+    }
+
+    #[test]
+    fn test_parse_rejects_oversized_hand() {
+        let input =
+            r#"[Deal "N:KQ4.QJ982.2.AKQ43 J653.A73.985.J97 9.K54.KQT732.652 AT872.T6.AJ64.T8"]"#;
+        let err = parse_deal_tag(input).unwrap_err();
+        assert!(
+            err.message.contains("more than 13 cards"),
+            "{}",
+            err.message
+        );
+    }
+
+    #[test]
+    fn test_to_ending() {
+        // Three cards each is an ending, not a deal
+        let pbn_deal = parse_deal_tag(r#"[Deal "N:AK.2.. QJ.3.. T9.4.. 87.5.."]"#).unwrap();
+        assert!(pbn_deal.to_deal().is_err());
+        let deal = pbn_deal.to_ending().unwrap();
+        assert_eq!(deal.hand(Position::West).len(), 3);
+
+        // Full deals are endings too
+        let input =
+            r#"[Deal "N:KQ4.QJ982..AKQ43 J653.A73.985.J97 9.K54.KQT732.652 AT872.T6.AJ64.T8"]"#;
+        let pbn_deal = parse_deal_tag(input).unwrap();
+        assert_eq!(pbn_deal.to_ending().unwrap(), pbn_deal.to_deal().unwrap());
+
+        // Uneven hands and duplicates are rejected
+        let err = parse_deal_tag(r#"[Deal "N:AK.2.. QJ.3.. T9.4.. 8.5.."]"#)
+            .unwrap()
+            .to_ending()
+            .unwrap_err();
+        assert!(err.message.contains("Hand W has 2 cards, expected 3"));
+        let err = parse_deal_tag(r#"[Deal "N:AK.2.. QJ.3.. T9.4.. A8.5.."]"#)
+            .unwrap()
+            .to_ending()
+            .unwrap_err();
+        assert!(err.message.contains("Duplicate card in hand W"));
+        let err = parse_deal_tag(r#"[Deal "N:- - - -"]"#)
+            .unwrap()
+            .to_ending()
+            .unwrap_err();
+        assert!(err.message.contains("no cards"));
+    }
+
     #[test]
     fn test_format_deal_tag() {
         let input =
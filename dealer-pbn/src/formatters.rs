@@ -1,5 +1,8 @@
+use crate::deal::ParseError;
 use chrono::{Datelike, Local};
-use dealer_core::{Deal, Position, Rank, Suit};
+use dealer_core::{Deal, Position, PositionExt, Rank, Suit, SuitExt, Vulnerability};
+use std::cmp::Reverse;
+use std::io::{self, Write};
 
 /// Print format for outputting deals
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -14,6 +17,8 @@ pub enum PrintFormat {
     PrintCompact,
     /// Print in single-line format
     PrintOneLine,
+    /// Print one line per hand with Unicode suit symbols
+    Pretty,
 }
 
 /// Format a deal in "printall" format (newspaper-style columns)
@@ -33,7 +38,7 @@ pub fn format_printall(deal: &Deal, board_number: usize) -> String {
     result.push_str(&format!("{:4}.\n", board_number + 1));
 
     // Print each suit row (spades, hearts, diamonds, clubs)
-    let suits = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+    let suits = Suit::DESCENDING;
     let positions = [
         Position::North,
         Position::East,
@@ -87,7 +92,7 @@ pub fn format_printew(deal: &Deal) -> String {
     let mut result = String::new();
 
     // Print each suit row (spades, hearts, diamonds, clubs)
-    let suits = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+    let suits = Suit::DESCENDING;
     let positions = [Position::West, Position::East];
 
     for &suit in &suits {
@@ -123,8 +128,81 @@ pub fn format_printew(deal: &Deal) -> String {
     result
 }
 
+/// Expand `%` variables in a PBN event template
+///
+/// `%b` becomes the board number, `%s` the seed (empty when there is none) and
+/// `%%` a literal `%`. Any other `%` sequence is copied through unchanged.
+/// The PBN formatters never call this themselves; expand a template first and
+/// pass the result as their event name.
+pub fn format_event_template(template: &str, board: usize, seed: Option<u32>) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+        match chars.peek() {
+            Some('b') => result.push_str(&board.to_string()),
+            Some('s') => {
+                if let Some(seed_val) = seed {
+                    result.push_str(&seed_val.to_string());
+                }
+            }
+            Some('%') => result.push('%'),
+            _ => {
+                result.push('%');
+                continue;
+            }
+        }
+        chars.next();
+    }
+    result
+}
+
+/// Settings for one PBN board written by [`format_printpbn_with`]
+///
+/// [`PbnBoardOptions::new`] gives the [`format_printpbn`] defaults: dealer and
+/// vulnerability rotating by board, the default event, and a `[Deal]` tag
+/// starting from North.
+#[derive(Debug, Clone, Copy)]
+pub struct PbnBoardOptions<'a> {
+    /// 0-based board index; the `[Board]` tag shows `board_number + 1`
+    pub board_number: usize,
+    /// Dealer, or `None` to rotate by board number
+    pub dealer: Option<Position>,
+    /// Vulnerability, or `None` to rotate by board number
+    pub vulnerability: Option<Vulnerability>,
+    /// Event title, written verbatim
+    pub event_name: Option<&'a str>,
+    /// Seed named in the default event
+    pub seed: Option<u32>,
+    /// Input file named in the default event
+    pub input_file: Option<&'a str>,
+    /// Seat the `[Deal]` tag starts from
+    pub deal_leader: Position,
+}
+
+impl PbnBoardOptions<'_> {
+    /// Defaults for board index `board_number`
+    pub fn new(board_number: usize) -> Self {
+        PbnBoardOptions {
+            board_number,
+            dealer: None,
+            vulnerability: None,
+            event_name: None,
+            seed: None,
+            input_file: None,
+            deal_leader: Position::North,
+        }
+    }
+}
+
 /// Format a deal in PBN (Portable Bridge Notation) format
 ///
+/// The event name is written as given, so a title like `50% club game` stays
+/// literal; use [`format_event_template`] first for `%b`/`%s` variables.
+///
 /// This includes all standard PBN tags with metadata:
 /// - Event, Site, Date
 /// - Board number
@@ -133,6 +211,9 @@ pub fn format_printew(deal: &Deal) -> String {
 /// - Vulnerability
 /// - Deal string
 /// - Contract info (placeholders)
+///
+/// The `[Deal]` tag starts from North, as dealer.exe writes it; use
+/// [`format_printpbn_with`] to start from another seat.
 pub fn format_printpbn(
     deal: &Deal,
     board_number: usize,
@@ -142,6 +223,34 @@ pub fn format_printpbn(
     seed: Option<u32>,
     input_file: Option<&str>,
 ) -> String {
+    format_printpbn_with(
+        deal,
+        &PbnBoardOptions {
+            dealer,
+            vulnerability,
+            event_name,
+            seed,
+            input_file,
+            ..PbnBoardOptions::new(board_number)
+        },
+    )
+}
+
+/// Format a deal in PBN format with the settings in `options`
+///
+/// Same as [`format_printpbn`], except that the `[Deal]` tag lists the hands
+/// clockwise from `options.deal_leader`, so an imported `E:` board is written
+/// back unchanged.
+pub fn format_printpbn_with(deal: &Deal, options: &PbnBoardOptions) -> String {
+    let PbnBoardOptions {
+        board_number,
+        dealer,
+        vulnerability,
+        event_name,
+        seed,
+        input_file,
+        deal_leader,
+    } = *options;
     let mut result = String::new();
 
     // Event tag - title takes precedence over seed/file
@@ -180,59 +289,21 @@ pub fn format_printpbn(
     result.push_str("[South \"-\"]\n");
 
     // Dealer - rotates by board number if not specified
-    let dealer_pos = dealer.unwrap_or(match board_number % 4 {
-        0 => Position::North,
-        1 => Position::East,
-        2 => Position::South,
-        _ => Position::West,
-    });
+    let dealer_pos = dealer.unwrap_or_else(|| Position::dealer_for_board(board_number + 1));
     result.push_str(&format!(
         "[Dealer \"{}\"]\n",
         position_char_upper(dealer_pos)
     ));
 
     // Vulnerability - rotates by board number if not specified
-    let vuln = vulnerability.unwrap_or_else(|| {
-        // Standard rotation: None, NS, EW, All, NS, EW, All, None, EW, All, None, NS, All, None, NS, EW
-        let board_vul = [0, 1, 2, 3, 1, 2, 3, 0, 2, 3, 0, 1, 3, 0, 1, 2];
-        match board_vul[board_number % 16] {
-            0 => Vulnerability::None,
-            1 => Vulnerability::NS,
-            2 => Vulnerability::EW,
-            _ => Vulnerability::All,
-        }
-    });
+    let vuln = vulnerability.unwrap_or_else(|| Vulnerability::for_board(board_number + 1));
     result.push_str(&format!(
         "[Vulnerable \"{}\"]\n",
         vulnerability_string(vuln)
     ));
 
-    // Deal tag
-    result.push_str("[Deal \"N:");
-    for pos in [
-        Position::North,
-        Position::East,
-        Position::South,
-        Position::West,
-    ] {
-        let hand = deal.hand(pos);
-        for suit in [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs] {
-            let mut cards: Vec<_> = hand.cards_in_suit(suit);
-            cards.sort_by(|a, b| b.rank.cmp(&a.rank)); // High to low
-
-            for card in cards {
-                result.push(rank_char(card.rank));
-            }
-
-            if suit != Suit::Clubs {
-                result.push('.');
-            }
-        }
-        if pos != Position::West {
-            result.push(' ');
-        }
-    }
-    result.push_str("\"]\n");
+    // Deal tag, hands listed clockwise from deal_leader (dealer.exe always uses North)
+    result.push_str(&format!("[Deal \"{}\"]\n", deal.to_pbn_string(deal_leader)));
 
     // Placeholder tags for game info
     result.push_str("[Declarer \"?\"]\n");
@@ -243,13 +314,60 @@ pub fn format_printpbn(
     result
 }
 
-/// Vulnerability enum for PBN format
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Vulnerability {
-    None,
-    NS,
-    EW,
-    All,
+/// Write a set of deals as one multi-board PBN file
+///
+/// Boards are numbered from `start_board` (1 for the first board of a set) and
+/// each is written as by [`format_printpbn`], so boards are separated by a blank
+/// line. When `dealer` or `vulnerability` is `None` it rotates by board number.
+///
+/// Board numbers start at 1, so a `start_board` of 0 is an
+/// [`io::ErrorKind::InvalidInput`] error and nothing is written.
+pub fn write_pbn_set<W: Write>(
+    writer: &mut W,
+    deals: &[Deal],
+    start_board: usize,
+    dealer: Option<Position>,
+    vulnerability: Option<Vulnerability>,
+) -> io::Result<()> {
+    if start_board == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "PBN board numbers start at 1",
+        ));
+    }
+    for (i, deal) in deals.iter().enumerate() {
+        // format_printpbn takes the 0-based board index
+        let board_number = start_board + i - 1;
+        let board = format_printpbn(deal, board_number, dealer, vulnerability, None, None, None);
+        writer.write_all(board.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// PBN parsing for dealer-core's `Vulnerability`
+///
+/// `Vulnerability` is defined in dealer-core, so its PBN tag parser is
+/// provided through an extension trait.
+pub trait VulnerabilityExt {
+    /// Parse the value of a PBN `[Vulnerable "..."]` tag
+    ///
+    /// Accepts every spelling the PBN standard allows, case-insensitively:
+    /// `None`/`Love`/`-`, `NS`, `EW` and `All`/`Both`.
+    fn from_pbn(s: &str) -> Result<Vulnerability, ParseError>;
+}
+
+impl VulnerabilityExt for Vulnerability {
+    fn from_pbn(s: &str) -> Result<Vulnerability, ParseError> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "none" | "love" | "-" => Ok(Vulnerability::None),
+            "ns" => Ok(Vulnerability::NS),
+            "ew" => Ok(Vulnerability::EW),
+            "all" | "both" => Ok(Vulnerability::All),
+            _ => Err(ParseError {
+                message: format!("Invalid vulnerability: {}", s),
+            }),
+        }
+    }
 }
 
 fn vulnerability_string(vuln: Vulnerability) -> &'static str {
@@ -300,6 +418,51 @@ fn position_char_lower(pos: Position) -> char {
     }
 }
 
+/// Format a deal with Unicode suit symbols, one line per hand
+///
+/// Voids are shown as an em dash. Example output:
+/// ```text
+/// North ♠ AKQT3 ♥ J6 ♦ KJ42 ♣ 95
+/// East  ♠ 652 ♥ AK42 ♦ AQ87 ♣ T4
+/// South ♠ J74 ♥ QT95 ♦ T ♣ AK863
+/// West  ♠ 98 ♥ 873 ♦ 9653 ♣ QJ72
+/// ```
+pub fn format_pretty(deal: &Deal) -> String {
+    let mut result = String::new();
+
+    for (pos, name) in [
+        (Position::North, "North"),
+        (Position::East, "East "),
+        (Position::South, "South"),
+        (Position::West, "West "),
+    ] {
+        result.push_str(name);
+        let hand = deal.hand(pos);
+        for (suit, symbol) in [
+            (Suit::Spades, '♠'),
+            (Suit::Hearts, '♥'),
+            (Suit::Diamonds, '♦'),
+            (Suit::Clubs, '♣'),
+        ] {
+            result.push(' ');
+            result.push(symbol);
+            result.push(' ');
+
+            let mut cards: Vec<_> = hand.cards_in_suit(suit);
+            cards.sort_by_key(|card| Reverse(card.rank)); // High to low
+            if cards.is_empty() {
+                result.push('—');
+            } else {
+                result.extend(cards.iter().map(|c| rank_char(c.rank)));
+            }
+        }
+        result.push('\n');
+    }
+    result.push('\n');
+
+    result
+}
+
 /// Format a deal in "printcompact" format (4 lines, one per position)
 ///
 /// Example output:
@@ -309,7 +472,13 @@ fn position_char_lower(pos: Position) -> char {
 /// s 9.K54.KQT732.652
 /// w AT872.T6.AJ64.T8
 /// ```
-pub fn format_printcompact(deal: &Deal) -> String {
+///
+/// If `value` is given (from `printcompact <expr>`), it is appended
+/// right-aligned to the north line:
+/// ```text
+/// n KQ4.QJ982..AKQ43    16
+/// ```
+pub fn format_printcompact(deal: &Deal, value: Option<i32>) -> String {
     let mut result = String::new();
 
     for pos in [
@@ -322,7 +491,7 @@ pub fn format_printcompact(deal: &Deal) -> String {
         result.push(' ');
 
         let hand = deal.hand(pos);
-        for suit in [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs] {
+        for suit in Suit::DESCENDING {
             let mut cards: Vec<_> = hand.cards_in_suit(suit);
             cards.sort_by(|a, b| b.rank.cmp(&a.rank)); // High to low
 
@@ -334,6 +503,16 @@ pub fn format_printcompact(deal: &Deal) -> String {
                 result.push('.');
             }
         }
+        if let (Position::North, Some(value)) = (pos, value) {
+            // Pad to the longest possible line ("n " + 13 cards + 3 dots)
+            let line_start = result.rfind('\n').map_or(0, |i| i + 1);
+            let width = result.len() - line_start;
+            result.push_str(&format!(
+                "{}{:>6}",
+                " ".repeat(18usize.saturating_sub(width)),
+                value
+            ));
+        }
         result.push('\n');
     }
 
@@ -345,7 +524,7 @@ pub fn format_printcompact(deal: &Deal) -> String {
 pub fn format_hand_pbn(hand: &dealer_core::Hand) -> String {
     let mut result = String::new();
 
-    for suit in [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs] {
+    for suit in Suit::DESCENDING {
         let mut cards: Vec<_> = hand.cards_in_suit(suit);
         cards.sort_by(|a, b| b.rank.cmp(&a.rank)); // High to low
 
@@ -364,6 +543,7 @@ pub fn format_hand_pbn(hand: &dealer_core::Hand) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parse_deal_tag;
     use dealer_core::DealGenerator;
 
     #[test]
@@ -469,11 +649,180 @@ mod tests {
         assert!(output.contains("[Vulnerable \"All\"]"));
     }
 
+    #[test]
+    fn test_printpbn_deal_leader_round_trip() {
+        let tag =
+            "[Deal \"E:J653.A73.985.J97 9.K54.KQT732.652 AT872.T6.AJ64.T8 KQ4.QJ982..AKQ43\"]";
+        let imported = parse_deal_tag(tag).unwrap();
+        assert_eq!(imported.first_seat, Position::East);
+        let deal = imported.to_deal().unwrap();
+        assert_eq!(deal.hand(Position::North).hcp(), 17);
+
+        // Formatting from the imported leader reproduces the tag exactly
+        let options = PbnBoardOptions {
+            deal_leader: imported.first_seat,
+            ..PbnBoardOptions::new(0)
+        };
+        let output = format_printpbn_with(&deal, &options);
+        assert!(output.contains(&format!("{}\n", tag)), "{}", output);
+        let reparsed = output
+            .lines()
+            .find(|line| line.starts_with("[Deal "))
+            .map(|line| parse_deal_tag(line).unwrap())
+            .unwrap();
+        assert_eq!(reparsed, imported);
+
+        // A North leader lists the same hands from North
+        let output = format_printpbn(&deal, 0, None, None, None, None, None);
+        assert!(output.contains(
+            "[Deal \"N:KQ4.QJ982..AKQ43 J653.A73.985.J97 9.K54.KQT732.652 AT872.T6.AJ64.T8\"]"
+        ));
+    }
+
+    #[test]
+    fn test_write_pbn_set() {
+        let deals = DealGenerator::new(1).generate_many(6);
+        let mut output = Vec::new();
+        write_pbn_set(&mut output, &deals, 5, None, None).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let boards: Vec<&str> = output.split("\n\n").filter(|b| !b.is_empty()).collect();
+        assert_eq!(boards.len(), deals.len());
+        let tag = |board: &str, name: &str| {
+            board
+                .lines()
+                .find(|line| line.starts_with(&format!("[{} ", name)))
+                .map(str::to_string)
+                .unwrap()
+        };
+        for (i, (board, deal)) in boards.iter().zip(&deals).enumerate() {
+            assert_eq!(tag(board, "Board"), format!("[Board \"{}\"]", i + 5));
+            let parsed = parse_deal_tag(&tag(board, "Deal")).unwrap();
+            assert_eq!(&parsed.to_deal().unwrap(), deal);
+        }
+
+        // Boards 5-10 rotate dealer N E S W N E; board 5 is NS vulnerable
+        let dealers: Vec<String> = boards.iter().map(|b| tag(b, "Dealer")).collect();
+        assert_eq!(dealers[0], "[Dealer \"N\"]");
+        assert_eq!(dealers[1], "[Dealer \"E\"]");
+        assert_eq!(dealers[4], "[Dealer \"N\"]");
+        assert_eq!(tag(boards[0], "Vulnerable"), "[Vulnerable \"NS\"]");
+
+        // Board 0 does not exist; it is rejected rather than written as board 1
+        let mut output = Vec::new();
+        let err = write_pbn_set(&mut output, &deals, 0, None, None).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(output.is_empty());
+
+        // A fixed dealer and vulnerability apply to every board
+        let mut output = Vec::new();
+        write_pbn_set(
+            &mut output,
+            &deals[..2],
+            1,
+            Some(Position::South),
+            Some(Vulnerability::All),
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches("[Dealer \"S\"]").count(), 2);
+        assert_eq!(output.matches("[Vulnerable \"All\"]").count(), 2);
+    }
+
+    #[test]
+    fn test_format_pretty() {
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let output = format_pretty(&deal);
+
+        // Seed 1 north: AKQT3.J6.KJ42.95
+        assert!(output.starts_with("North ♠ AKQT3 ♥ J6 ♦ KJ42 ♣ 95\n"));
+        assert_eq!(output.lines().filter(|l| !l.is_empty()).count(), 4);
+        for symbol in ['♠', '♥', '♦', '♣'] {
+            assert_eq!(output.matches(symbol).count(), 4);
+        }
+
+        let pbn =
+            r#"[Deal "N:KQ4.QJ982..AKQ43 J653.A73.985.J97 9.K54.KQT732.652 AT872.T6.AJ64.T8"]"#;
+        let deal = crate::parse_deal_tag(pbn).unwrap().to_deal().unwrap();
+        let output = format_pretty(&deal);
+        assert!(output.contains("North ♠ KQ4 ♥ QJ982 ♦ — ♣ AKQ43\n"));
+    }
+
+    #[test]
+    fn test_vulnerability_for_board() {
+        // The PBN formatter uses dealer-core's rotation for its 0-based board index
+        let deal = DealGenerator::new(1).generate();
+        let pbn = format_printpbn(&deal, 15, None, None, None, None, None);
+        assert!(pbn.contains("[Board \"16\"]"));
+        assert!(pbn.contains("[Dealer \"W\"]"));
+        assert!(pbn.contains("[Vulnerable \"EW\"]"));
+    }
+
+    #[test]
+    fn test_vulnerability_from_pbn() {
+        for s in ["None", "Love", "-", "none", "LOVE"] {
+            assert_eq!(Vulnerability::from_pbn(s).unwrap(), Vulnerability::None);
+        }
+        for s in ["NS", "ns"] {
+            assert_eq!(Vulnerability::from_pbn(s).unwrap(), Vulnerability::NS);
+        }
+        for s in ["EW", "ew"] {
+            assert_eq!(Vulnerability::from_pbn(s).unwrap(), Vulnerability::EW);
+        }
+        for s in ["All", "Both", "all", "BOTH"] {
+            assert_eq!(Vulnerability::from_pbn(s).unwrap(), Vulnerability::All);
+        }
+        assert!(Vulnerability::from_pbn("NE").is_err());
+        assert!(Vulnerability::from_pbn("").is_err());
+
+        // Round-trips through the formatter's spelling
+        for v in [
+            Vulnerability::None,
+            Vulnerability::NS,
+            Vulnerability::EW,
+            Vulnerability::All,
+        ] {
+            assert_eq!(Vulnerability::from_pbn(vulnerability_string(v)).unwrap(), v);
+        }
+    }
+
+    #[test]
+    fn test_event_template() {
+        assert_eq!(
+            format_event_template("Board %b, seed %s", 7, Some(42)),
+            "Board 7, seed 42"
+        );
+        assert_eq!(format_event_template("100%% %x %", 1, None), "100% %x %");
+        assert_eq!(format_event_template("Seed %s", 1, None), "Seed ");
+
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let title = format_event_template("Club #%b", 3, Some(5));
+        let output = format_printpbn(&deal, 2, None, None, Some(&title), Some(5), None);
+        assert!(output.contains("[Event \"Club #3\"]"));
+        let output = format_printpbn(&deal, 2, None, None, None, Some(5), None);
+        assert!(output.contains("[Event \"Hand simulated by dealer, seed 5\"]"));
+    }
+
+    #[test]
+    fn test_printpbn_title_with_literal_percent() {
+        let deal = DealGenerator::new(1).generate();
+        for title in ["50% club game", "100%% %b %s"] {
+            let output = format_printpbn(&deal, 0, None, None, Some(title), Some(5), None);
+            assert!(
+                output.contains(&format!("[Event \"{}\"]", title)),
+                "{}",
+                output
+            );
+        }
+    }
+
     #[test]
     fn test_format_printcompact() {
         let mut gen = DealGenerator::new(1);
         let deal = gen.generate();
-        let output = format_printcompact(&deal);
+        let output = format_printcompact(&deal, None);
 
         // Should have 4 lines (one per position)
         assert_eq!(output.lines().count(), 4);
@@ -487,4 +836,23 @@ mod tests {
             assert_eq!(line.matches('.').count(), 3);
         }
     }
+
+    #[test]
+    fn test_format_printcompact_with_value() {
+        let mut gen = DealGenerator::new(1);
+        let deal = gen.generate();
+        let output = format_printcompact(&deal, Some(14));
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "n AKQT3.J6.KJ42.95    14");
+        // Only the north line carries the value
+        assert_eq!(
+            output.lines().skip(1).collect::<Vec<_>>(),
+            format_printcompact(&deal, None)
+                .lines()
+                .skip(1)
+                .collect::<Vec<_>>()
+        );
+    }
 }
@@ -4,7 +4,11 @@ mod oneline;
 
 pub use deal::{format_deal_tag, parse_deal_tag, ParseError, PbnDeal};
 pub use formatters::{
-    format_hand_pbn, format_printall, format_printcompact, format_printew, format_printpbn,
-    PrintFormat, Vulnerability,
+    format_event_template, format_hand_pbn, format_pretty, format_printall, format_printcompact,
+    format_printew, format_printpbn, format_printpbn_with, write_pbn_set, PbnBoardOptions,
+    PrintFormat, VulnerabilityExt,
 };
 pub use oneline::{format_oneline, parse_oneline};
+
+// Vulnerability lives in dealer-core; re-exported for existing callers
+pub use dealer_core::Vulnerability;
@@ -1,5 +1,5 @@
 use crate::ParseError;
-use dealer_core::{Card, Deal, Hand, Position, Rank, Suit};
+use dealer_core::{Card, Deal, Hand, Position, Rank, RankExt, Suit, SuitExt};
 
 /// Parse a deal in dealer.exe oneline format
 /// Format: "n AKQT3.J6.KJ42.95 e 652.AK42.AQ87.T4 s J74.QT95.T.AK863 w 98.873.9653.QJ72"
@@ -92,7 +92,7 @@ fn parse_hand(s: &str) -> Result<Hand, ParseError> {
     }
 
     let mut hand = Hand::new();
-    let suits = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+    let suits = Suit::DESCENDING;
 
     for (suit_idx, &suit_str) in suits_str.iter().enumerate() {
         let suit = suits[suit_idx];
@@ -105,6 +105,11 @@ fn parse_hand(s: &str) -> Result<Hand, ParseError> {
         // Parse each card rank in the suit
         for c in suit_str.chars() {
             let rank = parse_rank(c)?;
+            if hand.len() == 13 {
+                return Err(ParseError {
+                    message: format!("Hand {} has more than 13 cards", s),
+                });
+            }
             hand.add_card(Card::new(suit, rank));
         }
     }
@@ -114,7 +119,7 @@ fn parse_hand(s: &str) -> Result<Hand, ParseError> {
 
 /// Format a hand in Spades.Hearts.Diamonds.Clubs format
 fn format_hand(hand: &Hand) -> String {
-    let suits = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+    let suits = Suit::DESCENDING;
     let mut result = Vec::new();
 
     for &suit in &suits {
@@ -136,24 +141,9 @@ fn format_hand(hand: &Hand) -> String {
 
 /// Parse a rank character
 fn parse_rank(c: char) -> Result<Rank, ParseError> {
-    match c.to_uppercase().next().unwrap() {
-        'A' => Ok(Rank::Ace),
-        'K' => Ok(Rank::King),
-        'Q' => Ok(Rank::Queen),
-        'J' => Ok(Rank::Jack),
-        'T' => Ok(Rank::Ten),
-        '9' => Ok(Rank::Nine),
-        '8' => Ok(Rank::Eight),
-        '7' => Ok(Rank::Seven),
-        '6' => Ok(Rank::Six),
-        '5' => Ok(Rank::Five),
-        '4' => Ok(Rank::Four),
-        '3' => Ok(Rank::Three),
-        '2' => Ok(Rank::Two),
-        _ => Err(ParseError {
-            message: format!("Invalid rank character: {}", c),
-        }),
-    }
+    Rank::from_char(c).ok_or_else(|| ParseError {
+        message: format!("Invalid rank character: {}", c),
+    })
 }
 
 /// Get rank character (uppercase)
@@ -156,7 +156,7 @@ fn main() {
         total_deals += 1;
 
         // Evaluate constraint
-        let passes = match constraint {
+        let passes = match &constraint {
             Some(expr) => match eval_with_context(expr, &variables, &deal) {
                 Ok(result) => result != 0,
                 Err(e) => {
@@ -93,6 +93,13 @@ impl FastSupervisor {
         }
     }
 
+    /// Skip the next `n` deals of the seed sequence without dealing them.
+    pub fn skip(&mut self, n: usize) {
+        for _ in 0..n {
+            self.generator.next_seed();
+        }
+    }
+
     /// Generate a batch of work units (just seeds).
     fn generate_batch(&mut self, count: usize) -> Vec<FastWorkUnit> {
         let mut units = Vec::with_capacity(count);
@@ -1,13 +1,17 @@
 mod fast_parallel;
 mod parallel;
+mod pbn_in;
 
 use clap::Parser;
 use dealer_core::{Deal, DealGenerator, FastDealConfig, Position};
-use dealer_eval::{eval, eval_with_context, extract_constraint, extract_variables, EvalContext};
-use dealer_parser::{ActionType, Expr, Statement, VulnerabilityType};
+use dealer_dds::{format_makeable_contracts, DoubleDummySolver};
+use dealer_eval::{
+    eval, eval_with_context, extract_constraint, extract_variables, render_template, EvalContext,
+};
+use dealer_parser::{ActionType, Expr, Statement, TemplatePart, VulnerabilityType};
 use dealer_pbn::{
-    format_hand_pbn, format_oneline, format_printall, format_printcompact, format_printew,
-    format_printpbn, Vulnerability,
+    format_hand_pbn, format_oneline, format_pretty, format_printall, format_printcompact,
+    format_printew, format_printpbn, Vulnerability,
 };
 use fast_parallel::{FastParallelConfig, FastSupervisor};
 use std::fs::OpenOptions;
@@ -27,9 +31,12 @@ struct Args {
     #[arg(short = 'p', long = "produce")]
     produce: Option<usize>,
 
-    /// Maximum number of hands to generate (defaults to 10000000)
-    /// Can be combined with --produce to limit both generated and produced counts
-    #[arg(short = 'g', long = "generate")]
+    /// Maximum number of hands to generate (defaults to 1000000 per deal to
+    /// produce, or 10000000 when no produce count applies)
+    /// Can be combined with --produce to limit both generated and produced counts.
+    /// Stopping at this cap short of the produce count prints a warning and
+    /// exits with status 3, after printing whatever did match.
+    #[arg(short = 'g', long = "generate", visible_alias = "max-generate")]
     generate: Option<usize>,
 
     /// Random seed for generation (defaults to current time)
@@ -48,6 +55,11 @@ struct Args {
     #[arg(long = "vulnerable")]
     vulnerability: Option<VulnerabilityArg>,
 
+    /// Keep dealer North and vulnerability None on every board instead of
+    /// rotating them by board number; --dealer and --vulnerable still apply
+    #[arg(long = "no-rotate")]
+    no_rotate: bool,
+
     /// Toggle verbose output - stats are hidden by default, -v shows them (matches dealer.exe -v behavior)
     #[arg(short = 'v', long = "verbose")]
     verbose: bool,
@@ -80,6 +92,10 @@ struct Args {
     #[arg(short = 'C', long = "CSV")]
     csv_file: Option<String>,
 
+    /// Write deals to this file instead of stdout (statistics still go to the console)
+    #[arg(short = 'o', long = "output", value_name = "FILE")]
+    output_file: Option<String>,
+
     /// Title metadata for PBN output
     #[arg(short = 'T', long = "title")]
     title: Option<String>,
@@ -105,6 +121,16 @@ struct Args {
     #[arg(long = "input-deals", value_name = "SOURCE")]
     input_deals: Option<String>,
 
+    /// Read PBN [Deal] tags from stdin and print each board's double-dummy table
+    /// instead of generating deals. Malformed boards are skipped with a warning.
+    #[arg(long = "pbn-in")]
+    pbn_in: bool,
+
+    /// Trace the first N trick-boundary decisions of the double-dummy solver
+    /// to stderr (0 = off). Needs a build with the solver2 feature.
+    #[arg(long = "xray", value_name = "N", default_value = "0")]
+    xray: usize,
+
     // Deprecated switches - parse them to show helpful error messages
     /// DEPRECATED: 2-way swapping mode (not supported - incompatible with predeal)
     #[arg(short = '2', hide = true)]
@@ -132,6 +158,7 @@ struct Args {
 
     /// Number of worker threads for parallel generation (0 = auto-detect, 1 = single-threaded)
     /// Matches DealerV2_4's -R switch. Default is 0 (auto-detect) for maximum performance.
+    /// Output for a given seed is the same whatever the thread count.
     #[arg(short = 'R', long = "threads", default_value = "0")]
     threads: usize,
 
@@ -139,6 +166,21 @@ struct Args {
     #[arg(long = "batch-size", default_value = "0")]
     batch_size: usize,
 
+    /// Test --generate deals against the condition and report how many were
+    /// accepted on stderr, printing no deals. --produce is ignored.
+    #[arg(long = "count-only")]
+    count_only: bool,
+
+    /// Print the parsed variables and condition to stderr in canonical form
+    /// before generating, to check how the script was understood.
+    #[arg(long = "echo-ast")]
+    echo_ast: bool,
+
+    /// Start generating at this deal of the seed's sequence (1 = first deal).
+    /// Earlier deals are skipped without being evaluated or counted as generated.
+    #[arg(long = "start-board", value_name = "N")]
+    start_board: Option<usize>,
+
     /// Use legacy mode: single-threaded with dealer.exe-compatible RNG.
     /// Required for bit-for-bit output comparison with dealer.exe.
     /// Without this flag, dealer3 uses a faster parallel algorithm that produces
@@ -154,6 +196,9 @@ enum OutputFormat {
     PrintPBN,
     PrintCompact,
     PrintOneLine,
+    Pretty,
+    /// Script-only: "printf" template, rendered per deal
+    PrintF,
 }
 
 impl std::str::FromStr for OutputFormat {
@@ -166,8 +211,9 @@ impl std::str::FromStr for OutputFormat {
             "printpbn" | "pbn" => Ok(OutputFormat::PrintPBN),
             "printcompact" | "compact" => Ok(OutputFormat::PrintCompact),
             "printoneline" | "oneline" => Ok(OutputFormat::PrintOneLine),
+            "pretty" => Ok(OutputFormat::Pretty),
             _ => Err(format!(
-                "Invalid format '{}'. Valid options: printall, printew, printpbn, printcompact, printoneline",
+                "Invalid format '{}'. Valid options: printall, printew, printpbn, printcompact, printoneline, pretty",
                 s
             )),
         }
@@ -249,7 +295,7 @@ impl From<VulnerabilityArg> for Vulnerability {
 /// Parse predeal card string (format: S8743,HA9,D642,CQT64)
 /// Returns a vector of cards
 fn parse_predeal_cards(card_str: &str) -> Result<Vec<dealer_core::Card>, String> {
-    use dealer_core::{Card, Rank, Suit};
+    use dealer_core::{Card, Rank, RankExt, Suit, SuitExt};
 
     let mut cards = Vec::new();
 
@@ -268,33 +314,14 @@ fn parse_predeal_cards(card_str: &str) -> Result<Vec<dealer_core::Card>, String>
         let mut chars = token.chars();
         let suit_char = chars.next().unwrap().to_uppercase().next().unwrap();
 
-        let suit = match suit_char {
-            'S' => Suit::Spades,
-            'H' => Suit::Hearts,
-            'D' => Suit::Diamonds,
-            'C' => Suit::Clubs,
-            _ => return Err(format!("Invalid suit character: {}", suit_char)),
-        };
+        let suit = Suit::from_char(suit_char)
+            .ok_or_else(|| format!("Invalid suit character: {}", suit_char))?;
 
         // Remaining characters are ranks
         for rank_char in chars {
             let rank_char = rank_char.to_uppercase().next().unwrap();
-            let rank = match rank_char {
-                'A' => Rank::Ace,
-                'K' => Rank::King,
-                'Q' => Rank::Queen,
-                'J' => Rank::Jack,
-                'T' => Rank::Ten,
-                '9' => Rank::Nine,
-                '8' => Rank::Eight,
-                '7' => Rank::Seven,
-                '6' => Rank::Six,
-                '5' => Rank::Five,
-                '4' => Rank::Four,
-                '3' => Rank::Three,
-                '2' => Rank::Two,
-                _ => return Err(format!("Invalid rank character: {}", rank_char)),
-            };
+            let rank = Rank::from_char(rank_char)
+                .ok_or_else(|| format!("Invalid rank character: {}", rank_char))?;
 
             cards.push(Card::new(suit, rank));
         }
@@ -471,6 +498,16 @@ fn main() {
             .as_micros() as u32
     });
 
+    // Deals of the seed's sequence to pass over before generation starts
+    let boards_to_skip = match args.start_board {
+        Some(0) => {
+            eprintln!("Error: --start-board must be at least 1");
+            std::process::exit(1);
+        }
+        Some(n) => n - 1,
+        None => 0,
+    };
+
     // Open CSV file if requested
     let mut csv_writer: Option<BufWriter<std::fs::File>> = None;
     if let Some(csv_arg) = &args.csv_file {
@@ -500,6 +537,40 @@ fn main() {
         csv_writer = Some(BufWriter::new(file));
     }
 
+    // Open deal output file if requested
+    let mut output_writer: Option<BufWriter<std::fs::File>> = None;
+    if let Some(ref output_file) = args.output_file {
+        let file = std::fs::File::create(output_file).unwrap_or_else(|e| {
+            eprintln!("Error creating output file '{}': {}", output_file, e);
+            std::process::exit(1);
+        });
+        output_writer = Some(BufWriter::new(file));
+    }
+
+    // Solver tracing only exists in the bridge-solver engine
+    if args.xray > 0 {
+        #[cfg(feature = "solver2")]
+        dealer_dds::solver2::set_xray_limit(args.xray);
+        #[cfg(not(feature = "solver2"))]
+        eprintln!("Warning: --xray needs a build with the solver2 feature; ignored");
+    }
+
+    // Batch double-dummy mode: solve boards from stdin instead of generating
+    if args.pbn_in {
+        let stdin = io::stdin();
+        let result = match output_writer.as_mut() {
+            Some(writer) => {
+                pbn_in::solve_boards(stdin.lock(), writer).and_then(|()| writer.flush())
+            }
+            None => pbn_in::solve_boards(stdin.lock(), &mut io::stdout().lock()),
+        };
+        if let Err(e) = result {
+            eprintln!("Error solving PBN boards: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     // Read constraint from input file or stdin
     let mut constraint_str = String::new();
     if let Some(ref input_file) = args.input_file {
@@ -533,11 +604,19 @@ fn main() {
     let mut produce_count_from_input: Option<usize> = None;
     let mut generate_count_from_input: Option<usize> = None;
     let mut format_from_input: Option<OutputFormat> = None;
+    // Value printed after each deal by "printcompact <expr>"
+    let mut compact_value_expr: Option<Expr> = None;
+    // Template rendered after each deal by "printf"
+    let mut printf_template: Vec<TemplatePart> = Vec::new();
     let mut dealer_from_input: Option<DealerPosition> = None;
     let mut vuln_from_input: Option<VulnerabilityArg> = None;
+    let mut rotate_from_input = true;
+    // "evalcontract" appends the makeable contracts after each deal
+    let mut eval_contract = false;
 
     // Track average statements: (label, expression, sum, count)
-    let mut averages: Vec<(Option<String>, Expr, f64, usize)> = Vec::new();
+    // The sum is an i64 so long runs cannot overflow or lose precision
+    let mut averages: Vec<(Option<String>, Expr, i64, usize)> = Vec::new();
 
     // Track frequency statements: (label, expression, histogram, range)
     use std::collections::HashMap;
@@ -550,7 +629,7 @@ fn main() {
     )> = Vec::new();
 
     // Track CSV report statements
-    use dealer_parser::{CsvTerm, Side};
+    use dealer_parser::CsvTerm;
     let mut csv_reports: Vec<Vec<CsvTerm>> = Vec::new();
 
     for statement in &program.statements {
@@ -568,13 +647,20 @@ fn main() {
                         ActionType::PrintAll => OutputFormat::PrintAll,
                         ActionType::PrintEW => OutputFormat::PrintEW,
                         ActionType::PrintPBN => OutputFormat::PrintPBN,
-                        ActionType::PrintCompact => OutputFormat::PrintCompact,
+                        ActionType::PrintCompact(value) => {
+                            compact_value_expr = value.clone();
+                            OutputFormat::PrintCompact
+                        }
                         ActionType::PrintOneLine => OutputFormat::PrintOneLine,
+                        ActionType::PrintF(parts) => {
+                            printf_template = parts.clone();
+                            OutputFormat::PrintF
+                        }
                     });
                 }
                 // Extract averages if present
                 for avg_spec in avg_specs {
-                    averages.push((avg_spec.label.clone(), avg_spec.expr.clone(), 0.0, 0));
+                    averages.push((avg_spec.label.clone(), avg_spec.expr.clone(), 0, 0));
                 }
                 // Extract frequencies if present
                 for freq_spec in freq_specs {
@@ -602,6 +688,8 @@ fn main() {
                     VulnerabilityType::All => VulnerabilityArg::All,
                 });
             }
+            Statement::Rotate(rotate) => rotate_from_input = *rotate,
+            Statement::EvalContract => eval_contract = true,
             Statement::CsvReport(terms) => {
                 csv_reports.push(terms.clone());
             }
@@ -614,35 +702,72 @@ fn main() {
     let program_variables = extract_variables(&program);
     let constraint = extract_constraint(&program);
 
+    if args.echo_ast {
+        for statement in &program.statements {
+            if let Statement::Assignment { name, expr } = statement {
+                eprintln!("{} = {}", name, expr);
+            }
+        }
+        if let Some(expr) = &constraint {
+            eprintln!("condition {}", expr);
+        }
+    }
+
     // Determine limits for generation
     // -g limits total hands generated, -p limits matching hands produced
     // When both are specified, stop when either limit is reached
     // dealer.exe defaults: -g 10000000 (10M), -p 40
     // IMPORTANT: We must respect the generate limit to match dealer.exe behavior.
     // Without this, dealer3 could run forever trying to produce rare hands.
-    let max_generate = args
-        .generate
-        .or(generate_count_from_input)
-        .unwrap_or(10_000_000);
-    let produce_count = args
-        .produce
-        .or(produce_count_from_input)
-        .unwrap_or_else(|| {
-            if args.generate.is_some() {
-                usize::MAX // No produce limit when only -g is specified
+    let produce_count = if args.count_only {
+        usize::MAX // Every generated deal is tested; none are printed
+    } else {
+        args.produce
+            .or(produce_count_from_input)
+            .unwrap_or_else(|| {
+                if args.generate.is_some() {
+                    usize::MAX // No produce limit when only -g is specified
+                } else {
+                    40 // dealer.exe default for -p
+                }
+            })
+    };
+    // The default cap scales with the produce count, so large runs of a
+    // common condition are not cut short by a fixed limit
+    let max_generate =
+        args.generate
+            .or(generate_count_from_input)
+            .unwrap_or(if produce_count == usize::MAX {
+                10_000_000
             } else {
-                40 // dealer.exe default for -p
-            }
-        });
+                produce_count.saturating_mul(1_000_000)
+            });
+
+    // Each deal takes a full double-dummy solve (20 strains and declarers),
+    // which can take minutes for a 13-card deal
+    if eval_contract && !args.quiet && !args.count_only {
+        eprintln!(
+            "Warning: evalcontract solves every produced deal double-dummy; each deal may take minutes"
+        );
+    }
 
     let output_format = args
         .format
         .or(format_from_input)
         .unwrap_or(OutputFormat::PrintAll); // Default format (matches dealer.exe)
 
-    let dealer_position = args.dealer.or(dealer_from_input);
+    // Unset dealer/vulnerability rotate by board number unless rotation is off
+    let rotate = rotate_from_input && !args.no_rotate;
 
-    let vulnerability = args.vulnerability.or(vuln_from_input);
+    let dealer_position = args
+        .dealer
+        .or(dealer_from_input)
+        .or((!rotate).then_some(DealerPosition::North));
+
+    let vulnerability = args
+        .vulnerability
+        .or(vuln_from_input)
+        .or((!rotate).then_some(VulnerabilityArg::None));
 
     // Start timing
     let start_time = SystemTime::now();
@@ -765,14 +890,15 @@ fn main() {
     let process_matching_deal =
         |deal: &Deal,
          produced: usize,
-         averages: &mut Vec<(Option<String>, Expr, f64, usize)>,
+         averages: &mut Vec<(Option<String>, Expr, i64, usize)>,
          frequencies: &mut Vec<(
             Option<String>,
             Expr,
             HashMap<i32, usize>,
             Option<(i32, i32)>,
         )>,
-         csv_writer: &mut Option<BufWriter<std::fs::File>>| {
+         csv_writer: &mut Option<BufWriter<std::fs::File>>,
+         output_writer: &mut Option<BufWriter<std::fs::File>>| {
             // Calculate averages for this matching deal
             if !averages.is_empty() || !frequencies.is_empty() {
                 let ctx = EvalContext::with_variables(deal, &program_variables);
@@ -780,7 +906,7 @@ fn main() {
                 for (_, expr, sum, count) in averages.iter_mut() {
                     match eval(expr, &ctx) {
                         Ok(val) => {
-                            *sum += val as f64;
+                            *sum += i64::from(val);
                             *count += 1;
                         }
                         Err(e) => {
@@ -805,7 +931,7 @@ fn main() {
             }
 
             // In quiet mode, don't print deals (only statistics)
-            if !args.quiet {
+            if !args.quiet && !args.count_only {
                 let output = match output_format {
                     OutputFormat::PrintAll => format_printall(deal, produced),
                     OutputFormat::PrintEW => format_printew(deal),
@@ -824,10 +950,54 @@ fn main() {
                             input_file,
                         )
                     }
-                    OutputFormat::PrintCompact => format_printcompact(deal),
+                    OutputFormat::PrintCompact => {
+                        let value = compact_value_expr.as_ref().map(|expr| {
+                            let ctx = EvalContext::with_variables(deal, &program_variables);
+                            match eval(expr, &ctx) {
+                                Ok(val) => val,
+                                Err(e) => {
+                                    eprintln!("Printcompact evaluation error: {}", e);
+                                    std::process::exit(1);
+                                }
+                            }
+                        });
+                        format_printcompact(deal, value)
+                    }
                     OutputFormat::PrintOneLine => format_oneline(deal),
+                    OutputFormat::Pretty => format_pretty(deal),
+                    OutputFormat::PrintF => {
+                        let ctx = EvalContext::with_variables(deal, &program_variables);
+                        match render_template(&printf_template, &ctx) {
+                            Ok(line) => format!("{}\n", line),
+                            Err(e) => {
+                                eprintln!("Printf evaluation error: {}", e);
+                                std::process::exit(1);
+                            }
+                        }
+                    }
                 };
-                print!("{}", output);
+                let output = if eval_contract {
+                    let dds = DoubleDummySolver::new(deal.clone()).solve_all();
+                    let contracts = format_makeable_contracts(&dds);
+                    if output_format == OutputFormat::PrintPBN {
+                        // PBN commentary inside the board, before its closing blank line
+                        let mut board = output;
+                        board.pop();
+                        format!("{}{{{}}}\n\n", board, contracts.trim_end())
+                    } else {
+                        format!("{}{}", output, contracts)
+                    }
+                } else {
+                    output
+                };
+                if let Some(writer) = output_writer.as_mut() {
+                    write!(writer, "{}", output).unwrap_or_else(|e| {
+                        eprintln!("Output write error: {}", e);
+                        std::process::exit(1);
+                    });
+                } else {
+                    print!("{}", output);
+                }
             }
 
             // Write CSV reports if any
@@ -854,10 +1024,7 @@ fn main() {
                                 line_parts.push(format_hand_pbn(hand));
                             }
                             CsvTerm::Side(side) => {
-                                let (pos1, pos2) = match side {
-                                    Side::NS => (Position::North, Position::South),
-                                    Side::EW => (Position::East, Position::West),
-                                };
+                                let [pos1, pos2] = side.positions();
                                 let hand1 = deal.hand(pos1);
                                 let hand2 = deal.hand(pos2);
                                 line_parts.push(format!(
@@ -947,7 +1114,7 @@ fn main() {
             }
 
             // Evaluate constraint
-            let eval_result = match constraint {
+            let eval_result = match &constraint {
                 Some(expr) => eval_with_context(expr, &program_variables, &deal),
                 None => Ok(1),
             };
@@ -960,6 +1127,7 @@ fn main() {
                         &mut averages,
                         &mut frequencies,
                         &mut csv_writer,
+                        &mut output_writer,
                     );
                     produced += 1;
                     if produced >= produce_count {
@@ -1014,6 +1182,8 @@ fn main() {
             }
         }
 
+        generator.skip(boards_to_skip);
+
         while produced < produce_count && generated < max_generate {
             // Check timeout (check every 1000 deals to avoid excessive time calls)
             if let Some(timeout_secs) = args.timeout {
@@ -1044,7 +1214,7 @@ fn main() {
             }
 
             // Evaluate constraint with pre-extracted variables (optimized hot path)
-            let eval_result = match constraint {
+            let eval_result = match &constraint {
                 Some(expr) => eval_with_context(expr, &program_variables, &deal),
                 None => Ok(1), // No constraint = always match
             };
@@ -1058,6 +1228,7 @@ fn main() {
                         &mut averages,
                         &mut frequencies,
                         &mut csv_writer,
+                        &mut output_writer,
                     );
                     produced += 1;
                 }
@@ -1083,6 +1254,7 @@ fn main() {
         } else {
             FastSupervisor::new(seed as u64, config)
         };
+        supervisor.skip(boards_to_skip);
 
         let actual_batch_size = if args.batch_size == 0 {
             200 * if args.threads == 0 {
@@ -1121,7 +1293,7 @@ fn main() {
             // Process batch in parallel
             // The filter closure evaluates the constraint for each deal
             let results = supervisor.process_batch(batch_size, |deal| {
-                match constraint {
+                match &constraint {
                     Some(expr) => {
                         // Note: This creates a new EvalContext for each deal in parallel
                         // The program_variables are shared (read-only)
@@ -1155,6 +1327,7 @@ fn main() {
                         &mut averages,
                         &mut frequencies,
                         &mut csv_writer,
+                        &mut output_writer,
                     );
                     produced += 1;
 
@@ -1169,6 +1342,22 @@ fn main() {
         }
     }
 
+    // Make sure every deal reaches the output file before reporting
+    if let Some(writer) = output_writer.as_mut() {
+        writer.flush().unwrap_or_else(|e| {
+            eprintln!("Output write error: {}", e);
+            std::process::exit(1);
+        });
+    }
+    // The CSV file too: a run stopped at the generate cap exits without
+    // dropping it
+    if let Some(writer) = csv_writer.as_mut() {
+        writer.flush().unwrap_or_else(|e| {
+            eprintln!("CSV write error: {}", e);
+            std::process::exit(1);
+        });
+    }
+
     // Calculate elapsed time
     let elapsed = start_time.elapsed().unwrap();
     let elapsed_secs = elapsed.as_secs_f64();
@@ -1178,7 +1367,7 @@ fn main() {
     if !averages.is_empty() {
         for (label, _, sum, count) in &averages {
             let avg = if *count > 0 {
-                sum / (*count as f64)
+                *sum as f64 / *count as f64
             } else {
                 0.0
             };
@@ -1260,8 +1449,37 @@ fn main() {
         println!("Time needed  {:7.3} sec", elapsed_secs);
     }
 
+    if args.count_only {
+        let percent = if generated > 0 {
+            100.0 * produced as f64 / generated as f64
+        } else {
+            0.0
+        };
+        eprintln!("Accepted {}/{} ({:.2}%)", produced, generated, percent);
+    }
+
     // Exit with error code if timed out
     if timed_out {
         std::process::exit(2);
     }
+
+    // Fail when the generate cap, not the produce count, ended the run. A
+    // generate count with no produce count (or -c) leaves nothing to fall
+    // short of: generating that many and keeping every match is the whole run
+    let produce_limit_given = args.produce.is_some()
+        || produce_count_from_input.is_some()
+        || (args.generate.is_none() && generate_count_from_input.is_none());
+    if args.input_deals.is_none()
+        && !args.count_only
+        && produce_limit_given
+        && produced < produce_count
+        && generated >= max_generate
+    {
+        eprintln!(
+            "Warning: stopped at the generate limit of {} hands with {} of {} deals produced",
+            max_generate, produced, produce_count
+        );
+        eprintln!("The condition may be too strict or impossible; raise the limit with -g N");
+        std::process::exit(3);
+    }
 }
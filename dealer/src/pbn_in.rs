@@ -0,0 +1,36 @@
+//! Batch double-dummy mode (`--pbn-in`).
+//!
+//! Instead of generating deals, reads `[Deal "..."]` tags and prints the
+//! double-dummy table of each board. Endings (every hand holding the same
+//! number of cards) are accepted as well as full deals.
+
+use dealer_dds::format_dd_table;
+use dealer_pbn::parse_deal_tag;
+use std::io::{self, BufRead, Write};
+
+/// Solve every `[Deal]` tag in `input`, writing the tag and its table to `output`.
+///
+/// Malformed boards are reported on stderr and skipped; other lines are ignored.
+pub fn solve_boards<R: BufRead, W: Write>(input: R, output: &mut W) -> io::Result<()> {
+    let mut board = 0;
+
+    for line in input.lines() {
+        let line = line?;
+        let tag = line.trim();
+        if !tag.starts_with("[Deal ") {
+            continue;
+        }
+        board += 1;
+
+        match parse_deal_tag(tag).and_then(|pbn_deal| pbn_deal.to_ending()) {
+            Ok(deal) => {
+                writeln!(output, "{}", tag)?;
+                write!(output, "{}", format_dd_table(&deal))?;
+                writeln!(output)?;
+            }
+            Err(e) => eprintln!("Warning: skipping board {}: {}", board, e),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,441 @@
+/// Integration tests for the `average` and `frequency` actions
+///
+/// Runs the dealer binary in legacy mode with a fixed seed and checks the
+/// reported statistics against the same deals generated directly.
+use dealer_core::{DealGenerator, Position};
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Run the dealer binary on a program and collect its exit status and output
+fn run_dealer_output(program: &str, args: &[&str]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_dealer"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to start dealer");
+
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(program.as_bytes())
+        .expect("Failed to write program");
+
+    child.wait_with_output().expect("Failed to run dealer")
+}
+
+/// Run the dealer binary on a program and return its stdout
+fn run_dealer(program: &str, args: &[&str]) -> String {
+    let output = run_dealer_output(program, args);
+    assert!(
+        output.status.success(),
+        "dealer failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn test_average_hcp_north_seed_1() {
+    let output = run_dealer(
+        "produce 100\naverage \"north hcp\" hcp(north)\n",
+        &["-s", "1", "--legacy", "-q"],
+    );
+
+    let line = output
+        .lines()
+        .find(|l| l.starts_with("north hcp: "))
+        .unwrap_or_else(|| panic!("No average line in output:\n{}", output));
+    let reported: f64 = line["north hcp: ".len()..].parse().unwrap();
+
+    // Without a condition every generated deal is produced
+    let mut generator = DealGenerator::new(1);
+    let total: u32 = (0..100)
+        .map(|_| generator.generate().hand(Position::North).hcp() as u32)
+        .sum();
+    let expected = total as f64 / 100.0;
+
+    assert!(
+        (reported - expected).abs() < 1e-9,
+        "reported {}, expected {}",
+        reported,
+        expected
+    );
+}
+
+#[test]
+fn test_frequency_hcp_north_seed_1() {
+    // A narrow range so both the Low and High rows are exercised
+    let output = run_dealer(
+        "produce 100\nfrequency \"HCP\" hcp(north) 8 12\n",
+        &["-s", "1", "--legacy", "-q"],
+    );
+
+    let mut generator = DealGenerator::new(1);
+    let mut counts = [0usize; 38];
+    for _ in 0..100 {
+        counts[generator.generate().hand(Position::North).hcp() as usize] += 1;
+    }
+
+    let mut expected = vec!["Frequency HCP:".to_string()];
+    let low: usize = counts[..8].iter().sum();
+    if low > 0 {
+        expected.push(format!("Low\t{:8}", low));
+    }
+    for (hcp, count) in counts.iter().enumerate().take(13).skip(8) {
+        expected.push(format!("{:5}\t{:8}", hcp, count));
+    }
+    let high: usize = counts[13..].iter().sum();
+    if high > 0 {
+        expected.push(format!("High\t{:8}", high));
+    }
+
+    let table: Vec<&str> = output
+        .lines()
+        .skip_while(|l| !l.starts_with("Frequency"))
+        .take(expected.len())
+        .collect();
+    assert_eq!(table, expected);
+    assert!(low > 0 && high > 0, "seed 1 should fill both outer bins");
+}
+
+#[test]
+fn test_threads_do_not_change_output() {
+    let program =
+        "condition hcp(north) >= 15 && spades(north) >= 5\nproduce 20\naction printoneline\n";
+    let single = run_dealer(program, &["-s", "7", "-R", "1"]);
+    let multi = run_dealer(program, &["-s", "7", "-R", "4", "--batch-size", "37"]);
+
+    assert_eq!(single.lines().filter(|l| l.starts_with("n ")).count(), 20);
+    assert_eq!(single, multi);
+}
+
+#[test]
+fn test_ternary_feeds_average() {
+    let output = run_dealer(
+        "produce 100\naction average \"strong\" hcp(north) >= 15 ? 100 : 0\n",
+        &["-s", "1", "--legacy", "-q"],
+    );
+
+    let mut generator = DealGenerator::new(1);
+    let strong = (0..100)
+        .filter(|_| generator.generate().hand(Position::North).hcp() >= 15)
+        .count();
+
+    assert!(
+        output.lines().any(|l| l == format!("strong: {}", strong)),
+        "expected {} strong hands in:\n{}",
+        strong,
+        output
+    );
+}
+
+#[test]
+fn test_start_board_skips_earlier_deals() {
+    let deal_lines = |output: String| -> Vec<String> {
+        output
+            .lines()
+            .filter(|l| l.starts_with("n "))
+            .map(str::to_string)
+            .collect()
+    };
+
+    for mode in [&["--legacy"][..], &[][..]] {
+        let full = deal_lines(run_dealer(
+            "produce 6\naction printoneline\n",
+            &[&["-s", "5"], mode].concat(),
+        ));
+        let tail = deal_lines(run_dealer(
+            "produce 3\naction printoneline\n",
+            &[&["-s", "5", "--start-board", "4"], mode].concat(),
+        ));
+        assert_eq!(tail, full[3..]);
+    }
+}
+
+/// Run the dealer binary on a program that should fail and return its stderr
+fn run_dealer_expecting_error(program: &str, args: &[&str]) -> String {
+    let output = run_dealer_output(program, args);
+    assert!(!output.status.success(), "dealer unexpectedly succeeded");
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn test_predeal_statement_places_cards() {
+    for mode in [&["--legacy"][..], &[][..]] {
+        let output = run_dealer(
+            "predeal south SAK.HQ.D.C2\nproduce 20\naction printoneline\n",
+            &[&["-s", "3"], mode].concat(),
+        );
+        let deals: Vec<&str> = output.lines().filter(|l| l.starts_with("n ")).collect();
+        assert_eq!(deals.len(), 20);
+        for deal in deals {
+            let south = deal
+                .split("s ")
+                .nth(1)
+                .and_then(|rest| rest.split(" w ").next())
+                .expect("oneline deal has a south hand");
+            let suits: Vec<&str> = south.split('.').collect();
+            assert!(suits[0].starts_with("AK"), "south {} in {}", south, deal);
+            assert!(suits[1].contains('Q'), "south {} in {}", south, deal);
+            assert!(suits[3].ends_with('2'), "south {} in {}", south, deal);
+        }
+    }
+}
+
+#[test]
+fn test_conflicting_predeal_is_rejected() {
+    let stderr = run_dealer_expecting_error(
+        "predeal north SA,HK\npredeal south SA\nproduce 1\n",
+        &["-s", "1"],
+    );
+    assert!(stderr.contains("Predeal error"), "stderr: {}", stderr);
+}
+
+#[test]
+fn test_pbn_in_solves_boards() {
+    // Two three-card endings around a malformed board. In the first each hand
+    // holds one suit; in the second North-South hold every top card.
+    let boards = "[Event \"endings\"]\n\
+                  [Deal \"N:AKQ... .AKQ.. ..AKQ. ...AKQ\"]\n\
+                  [Deal \"N:AK.Q.. QJ.3..\"]\n\
+                  [Deal \"N:AKQ... 432... .AKQ.. .432..\"]\n";
+    let output = run_dealer(boards, &["--pbn-in"]);
+
+    let tables: Vec<&str> = output.split("\n\n").filter(|t| !t.is_empty()).collect();
+    assert_eq!(tables.len(), 2, "output:\n{}", output);
+
+    assert!(tables[0].starts_with("[Deal \"N:AKQ... .AKQ.. ..AKQ. ...AKQ\"]\n"));
+    assert!(tables[0].contains("\nNorth   0  3  0  3  0\n"));
+    assert!(tables[0].contains("\nWest    0  0  3  0  3"));
+
+    assert!(tables[1].contains("\nNorth   3  3  3  3  3\n"));
+    assert!(tables[1].contains("\nEast    0  0  0  0  0\n"));
+}
+
+/// A three-card ending where each hand holds one suit
+const XRAY_BOARD: &str = "[Deal \"N:AKQ... .AKQ.. ..AKQ. ...AKQ\"]\n";
+
+#[test]
+fn test_xray_zero_is_a_no_op() {
+    let plain = run_dealer_output(XRAY_BOARD, &["--pbn-in"]);
+    let zero = run_dealer_output(XRAY_BOARD, &["--pbn-in", "--xray", "0"]);
+    assert!(zero.status.success());
+    assert_eq!(zero.stdout, plain.stdout);
+    assert_eq!(zero.stderr, plain.stderr);
+}
+
+#[test]
+#[cfg(feature = "solver2")]
+fn test_xray_traces_first_n_decisions() {
+    // The 20 solves pass far more than three trick boundaries, so exactly
+    // three trace lines are added to whatever the solver prints anyway
+    let stderr_lines = |xray: &str| {
+        let output = run_dealer_output(XRAY_BOARD, &["--pbn-in", "--xray", xray]);
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stderr).lines().count()
+    };
+    assert_eq!(stderr_lines("3"), stderr_lines("0") + 3);
+}
+
+#[test]
+#[cfg(not(feature = "solver2"))]
+fn test_xray_without_solver2_warns() {
+    let output = run_dealer_output(XRAY_BOARD, &["--pbn-in", "--xray", "3"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+    assert!(
+        stderr.contains("--xray needs a build with the solver2 feature"),
+        "stderr: {}",
+        stderr
+    );
+}
+
+#[test]
+fn test_impossible_condition_hits_generate_cap() {
+    for mode in [&["--legacy"][..], &[][..]] {
+        let stderr = run_dealer_expecting_error(
+            "condition hcp(north) > 40\nproduce 5\naction printoneline\n",
+            &[&["-s", "1", "--max-generate", "2000"], mode].concat(),
+        );
+        assert!(
+            stderr.contains("generate limit of 2000 hands with 0 of 5 deals produced"),
+            "stderr: {}",
+            stderr
+        );
+        assert!(stderr.contains("too strict"), "stderr: {}", stderr);
+    }
+}
+
+#[test]
+fn test_generate_only_run_does_not_warn() {
+    // -g alone sets no produce count, so reaching it is the normal end of the run
+    for condition in ["hcp(north) >= 20", "hcp(north) >= 37"] {
+        let output = run_dealer_output(
+            &format!("condition {}\naction printoneline\n", condition),
+            &["-s", "1", "-g", "200", "--legacy"],
+        );
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "{}: {}", condition, stderr);
+        assert!(
+            !stderr.contains("generate limit"),
+            "{}: {}",
+            condition,
+            stderr
+        );
+    }
+}
+
+#[test]
+fn test_partial_produce_at_generate_cap_fails() {
+    // Some deals match before the cap: they are still printed, but the run
+    // fell short of produce, so it exits with status 3
+    let output = run_dealer_output(
+        "condition hcp(north) >= 20\nproduce 1000\naction printoneline\n",
+        &["-s", "1", "-g", "2000", "--legacy"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(3), "stderr: {}", stderr);
+    assert!(
+        stderr.contains("generate limit of 2000 hands"),
+        "stderr: {}",
+        stderr
+    );
+    let deals = stdout.lines().filter(|l| l.starts_with("n ")).count();
+    assert!(deals > 0 && deals < 1000, "{} deals", deals);
+}
+
+#[test]
+fn test_printf_renders_template_per_deal() {
+    let output = run_dealer(
+        "produce 3\naction printf \"N:%{hcp(north)} S:%{hcp(south)}\"\n",
+        &["-s", "1", "--legacy"],
+    );
+
+    let mut generator = DealGenerator::new(1);
+    let expected: Vec<String> = (0..3)
+        .map(|_| {
+            let deal = generator.generate();
+            format!(
+                "N:{} S:{}",
+                deal.hand(Position::North).hcp(),
+                deal.hand(Position::South).hcp()
+            )
+        })
+        .collect();
+    let lines: Vec<&str> = output.lines().filter(|l| l.starts_with("N:")).collect();
+    assert_eq!(lines, expected);
+}
+
+#[test]
+fn test_count_only_reports_acceptance() {
+    let output = run_dealer_output(
+        "condition hcp(north) >= 15\nproduce 5\n",
+        &["-s", "1", "-g", "1000", "--count-only", "--legacy"],
+    );
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty(), "deals were printed");
+
+    // produce is ignored: all 1000 deals are tested
+    let mut generator = DealGenerator::new(1);
+    let accepted = (0..1000)
+        .filter(|_| generator.generate().hand(Position::North).hcp() >= 15)
+        .count();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let expected = format!(
+        "Accepted {}/1000 ({:.2}%)",
+        accepted,
+        accepted as f64 / 10.0
+    );
+    assert!(stderr.contains(&expected), "stderr: {}", stderr);
+}
+
+#[test]
+fn test_echo_ast_prints_canonical_program() {
+    let output = run_dealer_output(
+        "strong = hcp(north)>=15 and shape(north,any 4333)\ncondition strong && not (hcp(south)<8 or spades(south)<3)\nproduce 1\n",
+        &["-s", "1", "--echo-ast", "--legacy"],
+    );
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("strong = hcp(north) >= 15 && shape(north, any 4333)\n"),
+        "stderr: {}",
+        stderr
+    );
+    assert!(
+        stderr.contains("condition strong && !(hcp(south) < 8 || spades(south) < 3)\n"),
+        "stderr: {}",
+        stderr
+    );
+}
+
+#[test]
+fn test_no_rotate_pins_dealer_and_vulnerability() {
+    let tag_values = |output: &str, tag: &str| -> Vec<String> {
+        output
+            .lines()
+            .filter(|l| l.starts_with(tag))
+            .map(str::to_string)
+            .collect()
+    };
+
+    // Without pinning, boards 1-5 rotate dealer N E S W N
+    let rotating = run_dealer("produce 5\naction printpbn\n", &["-s", "1", "--legacy"]);
+    assert_eq!(tag_values(&rotating, "[Dealer ").len(), 5);
+    assert!(rotating.contains("[Dealer \"E\"]"));
+
+    for (program, args) in [
+        (
+            "produce 5\naction printpbn\n",
+            &["-s", "1", "--no-rotate"][..],
+        ),
+        ("rotate off\nproduce 5\naction printpbn\n", &["-s", "1"][..]),
+    ] {
+        let output = run_dealer(program, args);
+        assert_eq!(tag_values(&output, "[Dealer "), vec!["[Dealer \"N\"]"; 5]);
+        assert_eq!(
+            tag_values(&output, "[Vulnerable "),
+            vec!["[Vulnerable \"None\"]"; 5]
+        );
+    }
+}
+
+#[test]
+fn test_evalcontract_warns() {
+    // -g 0 stops before any deal is generated, so nothing is solved
+    for produce in [1, 500] {
+        let output = run_dealer_output(
+            &format!("produce {}\naction printoneline, evalcontract\n", produce),
+            &["-s", "1", "--legacy", "-g", "0"],
+        );
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.contains("Warning: evalcontract solves every produced deal double-dummy"),
+            "stderr: {}",
+            stderr
+        );
+    }
+}
+
+#[test]
+fn test_evalcontract_line_in_pbn_board() {
+    // One suit per hand: each side makes a grand slam in its own two suits and
+    // nothing in notrump. A full 13-card solve, so this takes a while in debug
+    let program = "predeal north SAKQJT98765432\n\
+                   predeal east HAKQJT98765432\n\
+                   predeal south DAKQJT98765432\n\
+                   produce 1\naction printpbn, evalcontract\n";
+    let output = run_dealer(program, &["-s", "1", "--legacy"]);
+
+    // The line is PBN commentary inside the board, which still ends in one blank line
+    assert!(
+        output.ends_with("[Result \"?\"]\n{NS: 7S 7D  EW: 7H 7C}\n\n"),
+        "output: {}",
+        output
+    );
+    assert_eq!(output.matches("\n\n").count(), 1, "output: {}", output);
+}
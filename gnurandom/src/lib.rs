@@ -10,6 +10,23 @@ pub struct GnuRandomState {
     rptr: usize,
 }
 
+impl GnuRandomState {
+    /// Rebuild a state from its raw parts, e.g. a checkpoint saved to disk.
+    /// Returns None if the pointers are out of range or not SEP_3 apart,
+    /// which no real generator state can produce.
+    pub fn from_parts(state: [i64; 31], fptr: usize, rptr: usize) -> Option<Self> {
+        if fptr >= 31 || rptr >= 31 || (fptr + 31 - rptr) % 31 != 3 {
+            return None;
+        }
+        Some(Self { state, fptr, rptr })
+    }
+
+    /// The raw parts of the state: (state table, front pointer, rear pointer)
+    pub fn parts(&self) -> ([i64; 31], usize, usize) {
+        (self.state, self.fptr, self.rptr)
+    }
+}
+
 // ============================================================================
 // Xoshiro256++ - Fast, high-quality PRNG for modern deal generation
 // ============================================================================
@@ -224,6 +241,18 @@ impl GnuRandom {
         }
     }
 
+    /// Advance the generator `steps` draws, leaving it exactly where `steps`
+    /// calls to next_u32() would, without computing the outputs.
+    ///
+    /// This is still one state update per step: the shuffle consumes a
+    /// value-dependent number of draws (it retries on predealt slots) and its
+    /// card order depends on each value, so skipping deals cannot use it.
+    pub fn jump(&mut self, steps: u64) {
+        for _ in 0..steps {
+            self.warmup_iteration();
+        }
+    }
+
     /// Capture the current RNG state for later restoration.
     /// This allows parallel workers to reproduce the exact same random sequence.
     pub fn capture_state(&self) -> GnuRandomState {
@@ -342,6 +371,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_state_parts_round_trip() {
+        let mut rng1 = GnuRandom::new();
+        rng1.srandom(7);
+        for _ in 0..45 {
+            rng1.next_u32();
+        }
+
+        let (table, fptr, rptr) = rng1.capture_state().parts();
+        let state = GnuRandomState::from_parts(table, fptr, rptr).unwrap();
+        let mut rng2 = GnuRandom::from_state(state);
+
+        for _ in 0..100 {
+            assert_eq!(rng1.next_u32(), rng2.next_u32());
+        }
+
+        // Pointers out of range or not SEP_3 apart are rejected
+        assert!(GnuRandomState::from_parts(table, 31, 28).is_none());
+        assert!(GnuRandomState::from_parts(table, 5, 4).is_none());
+        assert!(GnuRandomState::from_parts(table, 1, 29).is_some());
+    }
+
     #[test]
     fn test_multiple_state_captures() {
         // Simulate supervisor capturing states for multiple workers
@@ -383,6 +434,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_jump_matches_next_u32() {
+        for steps in [0u64, 1, 2, 30, 31, 52, 1000] {
+            let mut jumped = GnuRandom::new();
+            jumped.srandom(7);
+            jumped.jump(steps);
+
+            let mut stepped = GnuRandom::new();
+            stepped.srandom(7);
+            for _ in 0..steps {
+                stepped.next_u32();
+            }
+
+            assert_eq!(
+                jumped.capture_state().parts(),
+                stepped.capture_state().parts(),
+                "steps {}",
+                steps
+            );
+            assert_eq!(jumped.next_u32(), stepped.next_u32());
+        }
+    }
+
     // ========================================================================
     // Xoshiro256++ tests
     // ========================================================================